@@ -0,0 +1,54 @@
+//! Show a single node in several places, so a value can be repeated where
+//! it's read without being repeated where it's kept.
+use crate::code::{Code, Node, NodeId};
+use std::fmt;
+
+/// Why an alias can't show its node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasError {
+	Deleted,
+	/// The node contains the alias, or other aliases that could contain it
+	/// in turn.
+	Recursive,
+}
+
+impl fmt::Display for AliasError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			AliasError::Deleted => write!(f, "the aliased node was deleted"),
+			AliasError::Recursive => {
+				write!(f, "an alias can't show the group it's in or other aliases")
+			}
+		}
+	}
+}
+
+/// Whether an alias of `target` can be shown in `group`.
+pub fn check(code: &Code, target: NodeId, group: NodeId) -> Result<(), AliasError> {
+	if code.get(target).is_none() {
+		return Err(AliasError::Deleted);
+	}
+
+	let contains_alias = code.walk(target).into_iter().any(|node_id| {
+		matches!(
+			code.get(node_id).map(|placed| &placed.node),
+			Some(Node::Alias(_))
+		)
+	});
+	if contains_alias || code.path(group).contains(&target) {
+		return Err(AliasError::Recursive);
+	}
+
+	Ok(())
+}
+
+/// The node an alias node shows.
+pub fn target(code: &Code, alias: NodeId) -> Option<Result<NodeId, AliasError>> {
+	let placed = code.get(alias)?;
+	let Node::Alias(target) = placed.node else {
+		return None;
+	};
+
+	let group = placed.parent().unwrap_or(code.root());
+	Some(check(code, target, group).map(|()| target))
+}
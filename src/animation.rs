@@ -0,0 +1,73 @@
+//! Keep track of everything that moves on screen.
+use crate::code::NodeId;
+use anim::{easing::EasingMode, Options, Timeline};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// What an animation is for. Only one animation runs per purpose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Purpose {
+	/// The opacity of the selection highlight.
+	Selection,
+
+	/// The opacity of the insertion cursor.
+	CursorBlink,
+
+	/// How far the indent guide of the block holding the selection has lit
+	/// up.
+	Guide,
+
+	/// The opacity of a freshly inserted node.
+	Insertion(NodeId),
+}
+
+/// The running animations, keyed by their [`Purpose`].
+#[derive(Default)]
+pub struct Animations {
+	running: HashMap<Purpose, Timeline<f32>>,
+}
+
+impl Animations {
+	/// Starts an animation, replacing the one running for the same purpose.
+	pub fn start(&mut self, purpose: Purpose, options: Options<f32>) {
+		self.running.insert(purpose, options.begin_animation());
+	}
+
+	/// Moves an animation towards a new value, starting from wherever it
+	/// currently is, or from `rest` if it isn't running.
+	pub fn retarget(&mut self, purpose: Purpose, rest: f32, to: f32, duration: Duration) {
+		let from = self.value_or(purpose, rest);
+		self.start(
+			purpose,
+			Options::new(from, to)
+				.duration(duration)
+				.easing(anim::easing::cubic_ease().mode(EasingMode::Out)),
+		);
+	}
+
+	pub fn cancel(&mut self, purpose: Purpose) {
+		self.running.remove(&purpose);
+	}
+
+	/// The current value of an animation, if it's running.
+	pub fn value(&self, purpose: Purpose) -> Option<f32> {
+		self.running.get(&purpose).map(Timeline::value)
+	}
+
+	/// The current value of an animation, or `rest` if it isn't running.
+	pub fn value_or(&self, purpose: Purpose, rest: f32) -> f32 {
+		self.value(purpose).unwrap_or(rest)
+	}
+
+	pub fn is_animating(&self) -> bool {
+		self.running
+			.values()
+			.any(|timeline| timeline.status().is_animating())
+	}
+
+	/// Advances the animations, dropping the ones that have finished.
+	pub fn update(&mut self, now: Instant) {
+		self.running
+			.retain(|_, timeline| !timeline.update_with_time(now).is_completed());
+	}
+}
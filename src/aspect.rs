@@ -0,0 +1,74 @@
+//! Show, dim or hide each aspect of the code separately.
+use crate::code::{Aspect, Code, NodeId};
+use std::collections::HashMap;
+
+/// How nodes of an aspect are shown, from most to least visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Visibility {
+	#[default]
+	Shown,
+	Dimmed,
+	Hidden,
+}
+
+impl Visibility {
+	/// The opacity the content of a node is drawn with.
+	pub fn opacity(self) -> f32 {
+		match self {
+			Visibility::Shown => 1.0,
+			Visibility::Dimmed => 0.35,
+			Visibility::Hidden => 0.0,
+		}
+	}
+}
+
+/// The visibility of every aspect.
+#[derive(Debug, Default)]
+pub struct AspectFilter {
+	visibility: HashMap<Aspect, Visibility>,
+}
+
+impl AspectFilter {
+	pub fn visibility(&self, aspect: Aspect) -> Visibility {
+		self.visibility.get(&aspect).copied().unwrap_or_default()
+	}
+
+	/// Goes from shown to dimmed to hidden and back to shown.
+	pub fn cycle(&mut self, aspect: Aspect) {
+		let next = match self.visibility(aspect) {
+			Visibility::Shown => Visibility::Dimmed,
+			Visibility::Dimmed => Visibility::Hidden,
+			Visibility::Hidden => Visibility::Shown,
+		};
+		self.visibility.insert(aspect, next);
+	}
+
+	/// The visibility of a node by its own tags alone, the least visible of
+	/// its aspects.
+	pub fn own_visibility(&self, code: &Code, node_id: NodeId) -> Visibility {
+		code.get(node_id)
+			.into_iter()
+			.flat_map(|placed| &placed.aspects)
+			.map(|&aspect| self.visibility(aspect))
+			.max()
+			.unwrap_or_default()
+	}
+
+	/// The visibility of a node, which is never more visible than its
+	/// ancestors.
+	pub fn node_visibility(&self, code: &Code, node_id: NodeId) -> Visibility {
+		let mut visibility = Visibility::Shown;
+		let mut node = Some(node_id);
+
+		while let Some(node_id) = node {
+			visibility = visibility.max(self.own_visibility(code, node_id));
+			node = code.get(node_id).and_then(|placed| placed.parent());
+		}
+
+		visibility
+	}
+
+	pub fn is_visible(&self, code: &Code, node_id: NodeId) -> bool {
+		self.node_visibility(code, node_id) != Visibility::Hidden
+	}
+}
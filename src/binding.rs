@@ -0,0 +1,71 @@
+//! Tie every use of a name to its definition, so they change together.
+use crate::code::{Code, Node, NodeId};
+use crate::scope;
+use std::collections::HashSet;
+
+/// The node a text node should become once it's typed: a definition if it
+/// starts a binding like `name = value`, or a reference to the definition of
+/// the same name available at it.
+pub fn link(code: &Code, node_id: NodeId) -> Option<Node> {
+	let Node::Text(name) = &code.get(node_id)?.node else {
+		return None;
+	};
+	let name = name.trim();
+	if !is_name(name) {
+		return None;
+	}
+
+	let parent = code.get(node_id)?.parent()?;
+	if let [first, operator, ..] = code.children(parent) {
+		let is_binding = matches!(
+			&code.get(*operator)?.node,
+			Node::Text(operator) if operator.trim() == "="
+		);
+		if *first == node_id && is_binding {
+			return Some(Node::Definition(name.into()));
+		}
+	}
+
+	scope::visible(code, node_id)
+		.get(name)
+		.copied()
+		.map(Node::Reference)
+}
+
+/// The reference a group gives a new value to, like `name` in
+/// `name := value`.
+pub fn mutated(code: &Code, group: NodeId) -> Option<NodeId> {
+	let [reference, operator, ..] = code.children(group) else {
+		return None;
+	};
+
+	let is_reference = matches!(code.get(*reference)?.node, Node::Reference(_));
+	let is_mutation = matches!(
+		&code.get(*operator)?.node,
+		Node::Text(operator) if operator.trim() == ":="
+	);
+	(is_reference && is_mutation).then_some(*reference)
+}
+
+/// The references and member accesses whose definition is gone.
+pub fn dangling(code: &Code) -> HashSet<NodeId> {
+	code.walk(code.root())
+		.into_iter()
+		.filter(
+			|&node_id| match code.get(node_id).map(|placed| &placed.node) {
+				Some(Node::Reference(target) | Node::Member { object: target, .. }) => {
+					code.name(*target).is_none()
+				}
+				_ => false,
+			},
+		)
+		.collect()
+}
+
+pub fn is_name(string: &str) -> bool {
+	string
+		.chars()
+		.next()
+		.is_some_and(|c| c.is_alphabetic() || c == '_')
+		&& string.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
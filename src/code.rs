@@ -1,34 +1,98 @@
 use apply::Apply;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 
+#[derive(Clone)]
 pub struct Code {
 	nodes: HashMap<NodeId, PlacedNode>,
 	root: NodeId,
 	id_counter: NodeId,
 }
 
+#[derive(Clone)]
 pub struct PlacedNode {
-	id: NodeId,
 	parent: Option<NodeId>,
 	pub node: Node,
+	/// The aspects of the code the node belongs to, which can be hidden
+	pub aspects: HashSet<Aspect>,
+	/// How a group limits the identifiers available in it, or nothing for
+	/// groups that only lay out their children
+	pub scope: Option<Scope>,
 }
 
+#[derive(Clone)]
 pub enum Node {
-	Program(Vec<NodeId>),
+	Group(Group),
+	Text(String),
+	/// A value of a built-in type written out, like `3`, `true` or `"hello"`
+	Literal(String),
+	/// The name a binding introduces, which its references show
+	Definition(String),
+	/// A use of the definition with the given id, showing its current name
+	Reference(NodeId),
+	/// An operator between the operands next to it in a group
+	Operator(String),
+	/// A type standing for the value that provides it where it's used
+	Type(String),
+	/// Turns values of one type into another, written `(Str -> Int)`
+	Converter {
+		from: String,
+		to: String,
+	},
+	/// A member of the value `object` is bound to, written `object.member`
+	/// whether it's stored or computed
+	Member {
+		object: NodeId,
+		member: String,
+	},
+	/// An input the function it's in takes from the context it's used in
+	/// rather than from its caller, written `@Console`
+	Implicit(String),
+	/// An item of an external library, written `library/Item`, which makes
+	/// its name available like a definition
+	Import(String),
+	/// The node with the given id, shown here as well as where it is, so
+	/// editing either edits both
+	Alias(NodeId),
 	Pixel {
 		position: (usize, usize),
 		color: Color,
 	},
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Color {
 	Red,
 	Green,
 	Blue,
 }
 
+/// A concern of the code that can be switched on and off separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Aspect {
+	Docs,
+	Notes,
+	MemoryManagement,
+	ErrorHandling,
+}
+
+/// How a context combines its definitions with the enclosing ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+	/// Adds its definitions to the enclosing ones, from where they're made,
+	/// like inside functions and types
+	Additive,
+	/// Replaces the enclosing definitions of the same names everywhere in it,
+	/// like `f32(3 + 4 / 10)` makes all numbers `f32`
+	Overwriting,
+	/// Starts without any of the enclosing definitions, like files and modules
+	Isolating,
+}
+
 pub type NodeId = usize;
 
+pub type Group = Vec<NodeId>;
+
 impl Code {
 	pub fn new() -> Self {
 		Self {
@@ -38,6 +102,8 @@ impl Code {
 					PlacedNode {
 						node: Node::Group(Vec::new()),
 						parent: None,
+						aspects: HashSet::new(),
+						scope: Some(Scope::Isolating),
 					},
 				);
 				ns
@@ -51,17 +117,29 @@ impl Code {
 		self.nodes.get(&node_id)
 	}
 
-	/// Insert a node into a particular node group
+	pub fn get_mut(&mut self, node_id: NodeId) -> Option<&mut PlacedNode> {
+		self.nodes.get_mut(&node_id)
+	}
+
+	/// Insert a node at the end of a particular node group
 	pub fn insert(&mut self, node: Node, group: NodeId) -> Option<NodeId> {
+		self.insert_at(node, group, usize::MAX)
+	}
+
+	/// Insert a node into a particular node group before the child at `index`
+	pub fn insert_at(&mut self, node: Node, group: NodeId, index: usize) -> Option<NodeId> {
 		let id = self.id_counter;
+		let children = self.nodes.get_mut(&group)?.node.group_mut()?;
+		children.insert(index.min(children.len()), id);
 		self.nodes.insert(
 			id,
 			PlacedNode {
 				node,
 				parent: Some(group),
+				aspects: HashSet::new(),
+				scope: None,
 			},
 		);
-		self.nodes.get_mut(&group)?.node.group_mut()?.push(id);
 		self.id_counter += 1;
 		Some(id)
 	}
@@ -69,6 +147,94 @@ impl Code {
 	pub fn root(&self) -> NodeId {
 		self.root
 	}
+
+	/// The nodes from the root down to a particular node, including both
+	pub fn path(&self, node_id: NodeId) -> Vec<NodeId> {
+		let mut path = Vec::new();
+		let mut node = Some(node_id);
+		while let Some(node_id) = node {
+			path.push(node_id);
+			node = self.get(node_id).and_then(PlacedNode::parent);
+		}
+		path.reverse();
+		path
+	}
+
+	/// Remove a node together with everything in it, returning the ids of the
+	/// removed nodes. The root can't be removed
+	pub fn remove(&mut self, node_id: NodeId) -> Vec<NodeId> {
+		let Some(parent) = self.get(node_id).and_then(PlacedNode::parent) else {
+			return Vec::new();
+		};
+
+		if let Some(siblings) = self
+			.nodes
+			.get_mut(&parent)
+			.and_then(|placed| placed.node.group_mut())
+		{
+			siblings.retain(|&sibling| sibling != node_id);
+		}
+
+		let removed = self.walk(node_id);
+		for node_id in &removed {
+			self.nodes.remove(node_id);
+		}
+		removed
+	}
+
+	/// Move a range of a group's children into a new group in their place
+	pub fn wrap(&mut self, group: NodeId, range: Range<usize>) -> Option<NodeId> {
+		if range.is_empty() || range.end > self.children(group).len() {
+			return None;
+		}
+
+		let wrapper = self.insert_at(Node::Group(Vec::new()), group, range.end)?;
+		let wrapped = self
+			.nodes
+			.get_mut(&group)?
+			.node
+			.group_mut()?
+			.drain(range)
+			.collect::<Vec<_>>();
+
+		for &node_id in &wrapped {
+			if let Some(placed) = self.nodes.get_mut(&node_id) {
+				placed.parent = Some(wrapper);
+			}
+		}
+		*self.nodes.get_mut(&wrapper)?.node.group_mut()? = wrapped;
+		Some(wrapper)
+	}
+
+	/// A node and everything in it, in reading order
+	pub fn walk(&self, node_id: NodeId) -> Vec<NodeId> {
+		let mut nodes = Vec::new();
+		let mut stack = vec![node_id];
+		while let Some(node_id) = stack.pop() {
+			if self.get(node_id).is_some() {
+				nodes.push(node_id);
+				stack.extend(self.children(node_id).iter().rev());
+			}
+		}
+		nodes
+	}
+
+	/// The name of a definition or import node
+	pub fn name(&self, definition: NodeId) -> Option<&str> {
+		match &self.get(definition)?.node {
+			Node::Definition(name) => Some(name),
+			Node::Import(short) => short.rsplit('/').next(),
+			_ => None,
+		}
+	}
+
+	/// The children of a group node, or nothing for any other node
+	pub fn children(&self, node_id: NodeId) -> &[NodeId] {
+		match self.get(node_id).map(|placed| &placed.node) {
+			Some(Node::Group(group)) => group,
+			_ => &[],
+		}
+	}
 }
 
 impl PlacedNode {
@@ -77,6 +243,34 @@ impl PlacedNode {
 	}
 }
 
+impl Aspect {
+	pub const ALL: &'static [Aspect] = &[
+		Aspect::Docs,
+		Aspect::Notes,
+		Aspect::MemoryManagement,
+		Aspect::ErrorHandling,
+	];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Aspect::Docs => "docs",
+			Aspect::Notes => "notes",
+			Aspect::MemoryManagement => "memory management",
+			Aspect::ErrorHandling => "error handling",
+		}
+	}
+}
+
+impl Scope {
+	pub fn name(self) -> &'static str {
+		match self {
+			Scope::Additive => "additive",
+			Scope::Overwriting => "overwriting",
+			Scope::Isolating => "isolating",
+		}
+	}
+}
+
 impl Node {
 	pub fn group_mut(&mut self) -> Option<&mut Group> {
 		match self {
@@ -84,4 +278,12 @@ impl Node {
 			_ => None,
 		}
 	}
+
+	/// The text typed into a text node or a definition's name
+	pub fn text_mut(&mut self) -> Option<&mut String> {
+		match self {
+			Node::Text(ref mut text) | Node::Definition(ref mut text) => Some(text),
+			_ => None,
+		}
+	}
 }
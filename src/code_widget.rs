@@ -61,10 +61,25 @@ impl Axis {
 	}
 }
 
+/// How strongly a child of a [`Column`] is separated from the one above it.
+///
+/// Code has no empty lines, so logical paragraphs are told apart by a bigger
+/// line height instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Separation {
+	/// An ordinary line.
+	#[default]
+	Line,
+
+	/// The first line of a new group.
+	Group,
+}
+
 /// A container that distributes its contents vertically.
 #[allow(missing_debug_implementations)]
 pub struct Column<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer> {
 	spacing: f32,
+	group_spacing: f32,
 	padding: Padding,
 	width: Length,
 	height: Length,
@@ -72,6 +87,7 @@ pub struct Column<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer> {
 	align_items: Alignment,
 	clip: bool,
 	children: Vec<Element<'a, Message, Theme, Renderer>>,
+	separations: Vec<Separation>,
 }
 
 impl<'a, Message, Theme, Renderer> Column<'a, Message, Theme, Renderer>
@@ -100,12 +116,14 @@ where
 	pub fn from_vec(children: Vec<Element<'a, Message, Theme, Renderer>>) -> Self {
 		Self {
 			spacing: 0.0,
+			group_spacing: 0.0,
 			padding: Padding::ZERO,
 			width: Length::Shrink,
 			height: Length::Shrink,
 			max_width: f32::INFINITY,
 			align_items: Alignment::Start,
 			clip: false,
+			separations: vec![Separation::default(); children.len()],
 			children,
 		}
 	}
@@ -120,6 +138,13 @@ where
 		self
 	}
 
+	/// Sets the extra vertical spacing added above children that start a
+	/// [`Separation::Group`].
+	pub fn group_spacing(mut self, amount: impl Into<Pixels>) -> Self {
+		self.group_spacing = amount.into().0;
+		self
+	}
+
 	/// Sets the [`Padding`] of the [`Column`].
 	pub fn padding<P: Into<Padding>>(mut self, padding: P) -> Self {
 		self.padding = padding.into();
@@ -158,7 +183,17 @@ where
 	}
 
 	/// Adds an element to the [`Column`].
-	pub fn push(mut self, child: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+	pub fn push(self, child: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+		self.push_separated(child, Separation::Line)
+	}
+
+	/// Adds an element to the [`Column`] with the given [`Separation`] from
+	/// the previous one.
+	pub fn push_separated(
+		mut self,
+		child: impl Into<Element<'a, Message, Theme, Renderer>>,
+		separation: Separation,
+	) -> Self {
 		let child = child.into();
 		let size = child.as_widget().size_hint();
 
@@ -171,6 +206,7 @@ where
 		}
 
 		self.children.push(child);
+		self.separations.push(separation);
 		self
 	}
 
@@ -193,6 +229,14 @@ where
 	) -> Self {
 		children.into_iter().fold(self, Self::push)
 	}
+
+	/// Returns the space left above the child with the given [`Separation`].
+	fn space_above(&self, separation: Separation) -> f32 {
+		match separation {
+			Separation::Line => self.spacing,
+			Separation::Group => self.spacing + self.group_spacing,
+		}
+	}
 }
 
 impl<'a, Message, Renderer> Default for Column<'a, Message, Renderer>
@@ -237,7 +281,12 @@ where
 			.shrink(self.padding);
 		let axis = Axis::Vertical;
 		let trees = &mut tree.children;
-		let total_spacing = self.spacing * self.children.len().saturating_sub(1) as f32;
+		let total_spacing: f32 = self
+			.separations
+			.iter()
+			.skip(1)
+			.map(|&separation| self.space_above(separation))
+			.sum();
 		let max_cross = axis.cross(limits.max());
 
 		let mut fill_main_sum = 0;
@@ -330,7 +379,7 @@ where
 
 		for (i, node) in nodes.iter_mut().enumerate() {
 			if i > 0 {
-				main += self.spacing;
+				main += self.space_above(self.separations[i]);
 			}
 
 			let (mut x, mut y) = axis.pack(main, pad.1);
@@ -362,8 +411,6 @@ where
 
 			node.move_to_mut(Point::new(x, y));
 
-			*node = Node::with_children(node.size(), vec![*node]);
-
 			main += axis.main(node.size());
 		}
 
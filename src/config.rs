@@ -0,0 +1,16 @@
+//! Find the user's configuration files.
+use std::path::PathBuf;
+
+/// The file named by the environment `variable`, or `name` in the user's
+/// config directory.
+pub fn path(variable: &str, name: &str) -> Option<PathBuf> {
+	if let Some(path) = std::env::var_os(variable) {
+		return Some(path.into());
+	}
+
+	let config = std::env::var_os("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+	Some(config.join("bismuth").join(name))
+}
@@ -0,0 +1,244 @@
+//! The code open in each tab, with everything that belongs to it alone.
+use crate::code::{Code, NodeId};
+use crate::implicit::{self, Calls};
+use crate::library::Libraries;
+use crate::member;
+use crate::projection;
+use crate::scope;
+use crate::types;
+use crate::visual_code::VisualCode;
+use iced::widget::scrollable::Viewport;
+use std::cell::OnceCell;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+pub struct Document {
+	pub name: String,
+	/// The file the document is saved to, if it has one.
+	pub path: Option<PathBuf>,
+	pub code: Code,
+	pub visual: VisualCode,
+	/// Where the document was scrolled to when it was last shown.
+	pub viewport: Option<Viewport>,
+	history: History,
+	analysis: Analysis,
+}
+
+/// What's worked out about the code, which is redone whenever the code
+/// changes rather than every time it's shown.
+#[derive(Default)]
+pub struct Analysis {
+	/// The references to definitions not available where they're used.
+	pub out_of_scope: HashSet<NodeId>,
+	/// The types that don't stand for exactly one value.
+	pub unresolved_types: HashSet<NodeId>,
+	/// The member accesses that don't refer to a member.
+	pub unresolved_members: HashSet<NodeId>,
+	/// The values filling in the implicit inputs of every call that has
+	/// any.
+	pub calls: Calls,
+	/// The calls where an implicit input can't be filled in.
+	pub unfilled: HashSet<NodeId>,
+	/// The imports that don't refer to a single item, worked out the first
+	/// time they're needed after an edit, as the libraries they're looked
+	/// up in belong to the editor.
+	unresolved_imports: OnceCell<HashSet<NodeId>>,
+}
+
+impl Analysis {
+	fn new(code: &Code) -> Self {
+		let calls = implicit::calls(code);
+		let unfilled = calls
+			.iter()
+			.filter(|(_, arguments)| arguments.iter().any(|(_, provider)| provider.is_err()))
+			.map(|(&call, _)| call)
+			.collect();
+
+		Self {
+			out_of_scope: scope::out_of_scope(code),
+			unresolved_types: types::unresolved(code),
+			unresolved_members: member::unresolved(code),
+			calls,
+			unfilled,
+			unresolved_imports: OnceCell::new(),
+		}
+	}
+}
+
+/// Snapshots of the code to go back and forth between.
+#[derive(Default)]
+struct History {
+	undo: Vec<(Code, usize)>,
+	redo: Vec<(Code, usize)>,
+	/// Identifies the code as it is now, so going back to the same code
+	/// gives the same revision.
+	revision: usize,
+	/// The highest revision handed out so far.
+	latest: usize,
+	/// The revision the document was opened or last saved at.
+	saved: usize,
+	/// Whether edits are grouped into the last undo step, like while typing.
+	grouping: bool,
+	/// Whether an edit was made since grouping started.
+	grouped: bool,
+}
+
+impl Document {
+	pub fn new(name: impl Into<String>, code: Code) -> Self {
+		Self {
+			name: name.into(),
+			path: None,
+			analysis: Analysis::new(&code),
+			code,
+			visual: VisualCode::default(),
+			viewport: None,
+			history: History::default(),
+		}
+	}
+
+	/// Reads a document from a file written as text.
+	pub fn open(path: PathBuf) -> Result<Self, String> {
+		let text =
+			fs::read_to_string(&path).map_err(|error| format!("{}: {error}", path.display()))?;
+		let code =
+			projection::parse(&text).map_err(|error| format!("{}: {error}", path.display()))?;
+
+		let name = path.file_name().map_or_else(
+			|| path.display().to_string(),
+			|name| name.to_string_lossy().into(),
+		);
+		Ok(Self {
+			path: Some(path),
+			..Self::new(name, code)
+		})
+	}
+
+	/// Writes the code to the document's file as text. Returns whether reading
+	/// the file back gives the same code, which it doesn't for broken
+	/// references or aliases. Only then does the document count as saved, so
+	/// closing it still asks before losing what the file is missing.
+	pub fn save(&mut self) -> Result<bool, String> {
+		let path = self
+			.path
+			.as_ref()
+			.ok_or_else(|| format!("{} has no file to save to", self.name))?;
+
+		let text = projection::print(&self.code);
+		fs::write(path, &text).map_err(|error| format!("{}: {error}", path.display()))?;
+
+		let lossless =
+			projection::parse(&text).is_ok_and(|code| projection::equivalent(&code, &self.code));
+		if lossless {
+			self.history.saved = self.history.revision;
+			// Typing on after saving makes a revision of its own, or the
+			// document would look saved with the new text missing from the file.
+			self.history.grouped = false;
+		}
+		Ok(lossless)
+	}
+
+	pub fn analysis(&self) -> &Analysis {
+		&self.analysis
+	}
+
+	/// The imports that don't refer to a single item of the libraries.
+	pub fn unresolved_imports(&self, libraries: &Libraries) -> &HashSet<NodeId> {
+		self.analysis
+			.unresolved_imports
+			.get_or_init(|| libraries.unresolved(&self.code))
+	}
+
+	/// Whether the code changed since the document was opened or saved.
+	pub fn is_dirty(&self) -> bool {
+		self.history.revision != self.history.saved
+	}
+
+	/// Changes the code, remembering how it was so the change can be undone.
+	pub fn edit<T>(&mut self, edit: impl FnOnce(&mut Code) -> T) -> T {
+		let history = &mut self.history;
+
+		if !(history.grouping && history.grouped) {
+			history.undo.push((self.code.clone(), history.revision));
+			history.latest += 1;
+			history.revision = history.latest;
+		}
+		history.redo.clear();
+		history.grouped = history.grouping;
+
+		let result = edit(&mut self.code);
+		self.analysis = Analysis::new(&self.code);
+		result
+	}
+
+	/// Starts or stops grouping all edits into a single undo step.
+	pub fn group_edits(&mut self, grouping: bool) {
+		self.history.grouping = grouping;
+		self.history.grouped = false;
+	}
+
+	pub fn undo(&mut self) -> bool {
+		let Some(previous) = self.history.undo.pop() else {
+			return false;
+		};
+
+		let current = self.restore(previous);
+		self.history.redo.push(current);
+		true
+	}
+
+	pub fn redo(&mut self) -> bool {
+		let Some(next) = self.history.redo.pop() else {
+			return false;
+		};
+
+		let current = self.restore(next);
+		self.history.undo.push(current);
+		true
+	}
+
+	/// Replaces the code with a snapshot, returning a snapshot of the code
+	/// it replaced.
+	fn restore(&mut self, (code, revision): (Code, usize)) -> (Code, usize) {
+		self.history.grouped = false;
+		let current = (
+			std::mem::replace(&mut self.code, code),
+			std::mem::replace(&mut self.history.revision, revision),
+		);
+		self.analysis = Analysis::new(&self.code);
+		self.visual.forget_removed(&self.code);
+		current
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::code::Node;
+
+	#[test]
+	fn typing_after_saving() {
+		let path = std::env::temp_dir().join(format!("typing-after-saving-{}", std::process::id()));
+		let mut document = Document::new("test", Code::new());
+		document.path = Some(path.clone());
+		let root = document.code.root();
+
+		document.group_edits(true);
+		let word = document.edit(|code| code.insert(Node::Text("a".into()), root));
+		assert!(document.is_dirty());
+
+		assert_eq!(document.save(), Ok(true));
+		assert!(!document.is_dirty());
+
+		document.edit(|code| {
+			if let Some(Node::Text(text)) = word
+				.and_then(|word| code.get_mut(word))
+				.map(|placed| &mut placed.node)
+			{
+				text.push('b');
+			}
+		});
+		fs::remove_file(&path).ok();
+		assert!(document.is_dirty());
+	}
+}
@@ -1,14 +1,92 @@
 use iced::{
 	advanced::{layout, renderer, Renderer as _, Widget},
-	Color, Element, Length, Renderer, Size, Theme,
+	border::Radius,
+	Border, Color, Element, Length, Renderer, Size, Theme,
 };
 
-pub struct FillParent;
+/// An indent guide stretching along the block beside it.
+pub struct FillParent {
+	width: f32,
+	color: Color,
+	active_color: Color,
+	radius: f32,
+	active: bool,
+	intensity: f32,
+}
+
+impl FillParent {
+	pub fn new() -> Self {
+		Self {
+			width: 8.0,
+			color: Color::BLACK,
+			active_color: Color::WHITE,
+			radius: 0.0,
+			active: false,
+			intensity: 1.0,
+		}
+	}
+
+	pub fn width(mut self, width: f32) -> Self {
+		self.width = width;
+		self
+	}
+
+	/// Sets the color of the guide while the cursor is outside of its block.
+	pub fn color(mut self, color: Color) -> Self {
+		self.color = color;
+		self
+	}
+
+	/// Sets the color of the guide while the cursor is inside of its block.
+	pub fn active_color(mut self, color: Color) -> Self {
+		self.active_color = color;
+		self
+	}
+
+	pub fn radius(mut self, radius: f32) -> Self {
+		self.radius = radius;
+		self
+	}
+
+	/// Sets whether the cursor is inside of the guide's block.
+	pub fn active(mut self, active: bool) -> Self {
+		self.active = active;
+		self
+	}
+
+	/// Sets how far an active guide has moved from its `color` towards its
+	/// `active_color`, usually the current value of an animation.
+	pub fn intensity(mut self, intensity: f32) -> Self {
+		self.intensity = intensity.clamp(0.0, 1.0);
+		self
+	}
+
+	fn current_color(&self) -> Color {
+		if !self.active {
+			return self.color;
+		}
+
+		let mix = |from: f32, to: f32| from + (to - from) * self.intensity;
+
+		Color {
+			r: mix(self.color.r, self.active_color.r),
+			g: mix(self.color.g, self.active_color.g),
+			b: mix(self.color.b, self.active_color.b),
+			a: mix(self.color.a, self.active_color.a),
+		}
+	}
+}
+
+impl Default for FillParent {
+	fn default() -> Self {
+		Self::new()
+	}
+}
 
 impl<Message> Widget<Message, Theme, Renderer> for FillParent {
 	fn size(&self) -> iced::Size<iced::Length> {
 		Size {
-			width: Length::Fixed(8.),
+			width: Length::Fixed(self.width),
 			height: Length::Fill,
 		}
 	}
@@ -19,7 +97,7 @@ impl<Message> Widget<Message, Theme, Renderer> for FillParent {
 		_renderer: &Renderer,
 		limits: &iced::advanced::layout::Limits,
 	) -> iced::advanced::layout::Node {
-		layout::Node::new(Size::new(limits.max().width, limits.max().height))
+		layout::Node::new(Size::new(self.width, limits.max().height))
 	}
 
 	fn draw(
@@ -35,9 +113,14 @@ impl<Message> Widget<Message, Theme, Renderer> for FillParent {
 		renderer.fill_quad(
 			renderer::Quad {
 				bounds: layout.bounds(),
+				border: Border {
+					color: Color::TRANSPARENT,
+					width: 0.0,
+					radius: Radius::from(self.radius),
+				},
 				..renderer::Quad::default()
 			},
-			Color::BLACK,
+			self.current_color(),
 		);
 	}
 }
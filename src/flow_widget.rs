@@ -0,0 +1,374 @@
+//! Distribute content horizontally, wrapping onto continuation lines.
+use iced::advanced::layout;
+use iced::advanced::layout::Limits;
+use iced::advanced::layout::Node;
+use iced::advanced::overlay;
+use iced::advanced::renderer;
+use iced::advanced::widget::{Operation, Tree};
+use iced::event::{self, Event};
+use iced::mouse;
+use iced::Point;
+use iced::{
+	advanced::Clipboard, advanced::Layout, advanced::Shell, advanced::Widget, Element, Length,
+	Padding, Pixels, Rectangle, Size, Vector,
+};
+
+/// Whether a [`Flow`] may start a continuation line right before a child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Break {
+	/// The child always stays on the same line as the previous one.
+	Never,
+
+	/// The line may be broken here if there is no preferred point.
+	#[default]
+	Allowed,
+
+	/// The line is broken here before any other point.
+	Preferred,
+}
+
+/// A container that lays its contents out in a line and wraps them onto
+/// continuation lines when the available width is exceeded.
+#[allow(missing_debug_implementations)]
+pub struct Flow<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer> {
+	spacing: f32,
+	line_spacing: f32,
+	indent: f32,
+	padding: Padding,
+	width: Length,
+	height: Length,
+	children: Vec<Element<'a, Message, Theme, Renderer>>,
+	breaks: Vec<Break>,
+}
+
+impl<'a, Message, Theme, Renderer> Flow<'a, Message, Theme, Renderer>
+where
+	Renderer: iced::advanced::Renderer,
+{
+	/// Creates an empty [`Flow`].
+	pub fn new() -> Self {
+		Self {
+			spacing: 0.0,
+			line_spacing: 0.0,
+			indent: 0.0,
+			padding: Padding::ZERO,
+			width: Length::Shrink,
+			height: Length::Shrink,
+			children: Vec::new(),
+			breaks: Vec::new(),
+		}
+	}
+
+	/// Creates a [`Flow`] with the given elements.
+	pub fn with_children(
+		children: impl IntoIterator<Item = Element<'a, Message, Theme, Renderer>>,
+	) -> Self {
+		Self::new().extend(children)
+	}
+
+	/// Sets the horizontal spacing _between_ elements on the same line.
+	pub fn spacing(mut self, amount: impl Into<Pixels>) -> Self {
+		self.spacing = amount.into().0;
+		self
+	}
+
+	/// Sets the vertical spacing _between_ lines.
+	pub fn line_spacing(mut self, amount: impl Into<Pixels>) -> Self {
+		self.line_spacing = amount.into().0;
+		self
+	}
+
+	/// Sets the hanging indent of continuation lines.
+	pub fn indent(mut self, amount: impl Into<Pixels>) -> Self {
+		self.indent = amount.into().0;
+		self
+	}
+
+	/// Sets the [`Padding`] of the [`Flow`].
+	pub fn padding<P: Into<Padding>>(mut self, padding: P) -> Self {
+		self.padding = padding.into();
+		self
+	}
+
+	/// Sets the width of the [`Flow`].
+	pub fn width(mut self, width: impl Into<Length>) -> Self {
+		self.width = width.into();
+		self
+	}
+
+	/// Sets the height of the [`Flow`].
+	pub fn height(mut self, height: impl Into<Length>) -> Self {
+		self.height = height.into();
+		self
+	}
+
+	/// Adds an element to the [`Flow`].
+	pub fn push(self, child: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+		self.push_with_break(child, Break::Allowed)
+	}
+
+	/// Adds an element to the [`Flow`] with the given [`Break`] before it.
+	pub fn push_with_break(
+		mut self,
+		child: impl Into<Element<'a, Message, Theme, Renderer>>,
+		break_before: Break,
+	) -> Self {
+		self.children.push(child.into());
+		self.breaks.push(break_before);
+		self
+	}
+
+	/// Extends the [`Flow`] with the given children.
+	pub fn extend(
+		self,
+		children: impl IntoIterator<Item = Element<'a, Message, Theme, Renderer>>,
+	) -> Self {
+		children.into_iter().fold(self, Self::push)
+	}
+
+	/// Splits the children with the given widths into lines, returning the
+	/// index of the first child of every line.
+	fn break_lines(&self, widths: &[f32], max_width: f32) -> Vec<usize> {
+		let mut starts = vec![0];
+		let mut line_start = 0;
+		let mut i = 0;
+
+		while i < widths.len() {
+			let indent = if starts.len() > 1 { self.indent } else { 0.0 };
+			let line_width = indent
+				+ widths[line_start..=i].iter().sum::<f32>()
+				+ self.spacing * (i - line_start) as f32;
+
+			if line_width <= max_width || i == line_start {
+				i += 1;
+				continue;
+			}
+
+			let break_at = (line_start + 1..=i)
+				.filter(|&j| self.breaks[j] != Break::Never)
+				.max_by_key(|&j| (self.breaks[j], j));
+
+			match break_at {
+				Some(j) => {
+					starts.push(j);
+					line_start = j;
+					i = j;
+				}
+				None => i += 1,
+			}
+		}
+
+		starts
+	}
+}
+
+impl<'a, Message, Theme, Renderer> Default for Flow<'a, Message, Theme, Renderer>
+where
+	Renderer: iced::advanced::Renderer,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+	for Flow<'a, Message, Theme, Renderer>
+where
+	Renderer: iced::advanced::Renderer,
+{
+	fn children(&self) -> Vec<Tree> {
+		self.children.iter().map(Tree::new).collect()
+	}
+
+	fn diff(&self, tree: &mut Tree) {
+		tree.diff_children(&self.children);
+	}
+
+	fn size(&self) -> Size<Length> {
+		Size {
+			width: self.width,
+			height: self.height,
+		}
+	}
+
+	fn layout(
+		&self,
+		tree: &mut Tree,
+		renderer: &Renderer,
+		limits: &layout::Limits,
+	) -> layout::Node {
+		let limits = limits
+			.width(self.width)
+			.height(self.height)
+			.shrink(self.padding);
+		let max_width = limits.max().width;
+
+		let mut nodes = self
+			.children
+			.iter()
+			.zip(&mut tree.children)
+			.map(|(child, tree)| {
+				child.as_widget().layout(
+					tree,
+					renderer,
+					&Limits::new(Size::ZERO, Size::new(max_width, f32::INFINITY)),
+				)
+			})
+			.collect::<Vec<_>>();
+
+		let widths = nodes
+			.iter()
+			.map(|node| node.size().width)
+			.collect::<Vec<_>>();
+		let starts = self.break_lines(&widths, max_width);
+
+		let mut y = self.padding.top;
+		let mut intrinsic_width = 0.0f32;
+
+		for (line, &start) in starts.iter().enumerate() {
+			let end = starts.get(line + 1).copied().unwrap_or(nodes.len());
+			let mut x = self.padding.left + if line > 0 { self.indent } else { 0.0 };
+			let mut line_height = 0.0f32;
+
+			if line > 0 {
+				y += self.line_spacing;
+			}
+
+			for (i, node) in nodes[start..end].iter_mut().enumerate() {
+				if i > 0 {
+					x += self.spacing;
+				}
+
+				node.move_to_mut(Point::new(x, y));
+				x += node.size().width;
+				line_height = line_height.max(node.size().height);
+			}
+
+			intrinsic_width = intrinsic_width.max(x - self.padding.left);
+			y += line_height;
+		}
+
+		let size = limits.resolve(
+			self.width,
+			self.height,
+			Size::new(intrinsic_width, y - self.padding.top),
+		);
+
+		Node::with_children(size.expand(self.padding), nodes)
+	}
+
+	fn operate(
+		&self,
+		tree: &mut Tree,
+		layout: Layout<'_>,
+		renderer: &Renderer,
+		operation: &mut dyn Operation<Message>,
+	) {
+		operation.container(None, layout.bounds(), &mut |operation| {
+			self.children
+				.iter()
+				.zip(&mut tree.children)
+				.zip(layout.children())
+				.for_each(|((child, state), layout)| {
+					child
+						.as_widget()
+						.operate(state, layout, renderer, operation);
+				});
+		});
+	}
+
+	fn on_event(
+		&mut self,
+		tree: &mut Tree,
+		event: Event,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		renderer: &Renderer,
+		clipboard: &mut dyn Clipboard,
+		shell: &mut Shell<'_, Message>,
+		viewport: &Rectangle,
+	) -> event::Status {
+		self.children
+			.iter_mut()
+			.zip(&mut tree.children)
+			.zip(layout.children())
+			.map(|((child, state), layout)| {
+				child.as_widget_mut().on_event(
+					state,
+					event.clone(),
+					layout,
+					cursor,
+					renderer,
+					clipboard,
+					shell,
+					viewport,
+				)
+			})
+			.fold(event::Status::Ignored, event::Status::merge)
+	}
+
+	fn mouse_interaction(
+		&self,
+		tree: &Tree,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		viewport: &Rectangle,
+		renderer: &Renderer,
+	) -> mouse::Interaction {
+		self.children
+			.iter()
+			.zip(&tree.children)
+			.zip(layout.children())
+			.map(|((child, state), layout)| {
+				child
+					.as_widget()
+					.mouse_interaction(state, layout, cursor, viewport, renderer)
+			})
+			.max()
+			.unwrap_or_default()
+	}
+
+	fn draw(
+		&self,
+		tree: &Tree,
+		renderer: &mut Renderer,
+		theme: &Theme,
+		style: &renderer::Style,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		viewport: &Rectangle,
+	) {
+		for ((child, state), layout) in self
+			.children
+			.iter()
+			.zip(&tree.children)
+			.zip(layout.children())
+		{
+			child
+				.as_widget()
+				.draw(state, renderer, theme, style, layout, cursor, viewport);
+		}
+	}
+
+	fn overlay<'b>(
+		&'b mut self,
+		tree: &'b mut Tree,
+		layout: Layout<'_>,
+		renderer: &Renderer,
+		translation: Vector,
+	) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+		overlay::from_children(&mut self.children, tree, layout, renderer, translation)
+	}
+}
+
+impl<'a, Message, Theme, Renderer> From<Flow<'a, Message, Theme, Renderer>>
+	for Element<'a, Message, Theme, Renderer>
+where
+	Message: 'a,
+	Theme: 'a,
+	Renderer: iced::advanced::Renderer + 'a,
+{
+	fn from(flow: Flow<'a, Message, Theme, Renderer>) -> Self {
+		Self::new(flow)
+	}
+}
@@ -0,0 +1,349 @@
+//! Draw tree connectors beside a nested block of code.
+use iced::advanced::layout;
+use iced::advanced::layout::Limits;
+use iced::advanced::layout::Node;
+use iced::advanced::overlay;
+use iced::advanced::renderer;
+use iced::advanced::svg;
+use iced::advanced::widget::{Operation, Tree};
+use iced::event::{self, Event};
+use iced::mouse;
+use iced::{
+	advanced::Clipboard, advanced::Layout, advanced::Shell, advanced::Widget, Color, Element,
+	Length, Pixels, Point, Rectangle, Size, Vector,
+};
+
+/// One of the pieces a connector is assembled from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Branch {
+	/// The corner leading into the first child.
+	Top,
+
+	/// The straight line running along the children.
+	Mid,
+
+	/// The corner leading into the last child.
+	Bottom,
+}
+
+impl Branch {
+	/// The width of the [`Branch::Mid`] line relative to the corners.
+	const MID_RATIO: f32 = 0.2;
+
+	fn handle(self) -> svg::Handle {
+		svg::Handle::from_memory(match self {
+			Branch::Top => include_bytes!("../assets/branch_top.svg").as_slice(),
+			Branch::Mid => include_bytes!("../assets/branch_mid.svg").as_slice(),
+			Branch::Bottom => include_bytes!("../assets/branch_bottom.svg").as_slice(),
+		})
+	}
+}
+
+/// A container that distributes its contents vertically and connects them
+/// with a tree branch drawn in a gutter on their left.
+#[allow(missing_debug_implementations)]
+pub struct Gutter<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer> {
+	width: f32,
+	gap: f32,
+	spacing: f32,
+	color: Color,
+	children: Vec<Element<'a, Message, Theme, Renderer>>,
+}
+
+impl<'a, Message, Theme, Renderer> Gutter<'a, Message, Theme, Renderer>
+where
+	Renderer: svg::Renderer,
+{
+	/// Creates an empty [`Gutter`].
+	pub fn new() -> Self {
+		Self {
+			width: 12.0,
+			gap: 6.0,
+			spacing: 0.0,
+			color: Color::BLACK,
+			children: Vec::new(),
+		}
+	}
+
+	/// Creates a [`Gutter`] with the given elements.
+	pub fn with_children(
+		children: impl IntoIterator<Item = Element<'a, Message, Theme, Renderer>>,
+	) -> Self {
+		Self::new().extend(children)
+	}
+
+	/// Sets the width of the connector.
+	pub fn width(mut self, width: impl Into<Pixels>) -> Self {
+		self.width = width.into().0;
+		self
+	}
+
+	/// Sets the horizontal space between the connector and the children.
+	pub fn gap(mut self, gap: impl Into<Pixels>) -> Self {
+		self.gap = gap.into().0;
+		self
+	}
+
+	/// Sets the vertical spacing _between_ elements.
+	pub fn spacing(mut self, amount: impl Into<Pixels>) -> Self {
+		self.spacing = amount.into().0;
+		self
+	}
+
+	/// Sets the [`Color`] of the connector.
+	pub fn color(mut self, color: impl Into<Color>) -> Self {
+		self.color = color.into();
+		self
+	}
+
+	/// Adds an element to the [`Gutter`].
+	pub fn push(mut self, child: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+		self.children.push(child.into());
+		self
+	}
+
+	/// Extends the [`Gutter`] with the given children.
+	pub fn extend(
+		self,
+		children: impl IntoIterator<Item = Element<'a, Message, Theme, Renderer>>,
+	) -> Self {
+		children.into_iter().fold(self, Self::push)
+	}
+}
+
+impl<'a, Message, Theme, Renderer> Default for Gutter<'a, Message, Theme, Renderer>
+where
+	Renderer: svg::Renderer,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+	for Gutter<'a, Message, Theme, Renderer>
+where
+	Renderer: svg::Renderer,
+{
+	fn children(&self) -> Vec<Tree> {
+		self.children.iter().map(Tree::new).collect()
+	}
+
+	fn diff(&self, tree: &mut Tree) {
+		tree.diff_children(&self.children);
+	}
+
+	fn size(&self) -> Size<Length> {
+		Size {
+			width: Length::Shrink,
+			height: Length::Shrink,
+		}
+	}
+
+	fn layout(
+		&self,
+		tree: &mut Tree,
+		renderer: &Renderer,
+		limits: &layout::Limits,
+	) -> layout::Node {
+		let indent = self.width + self.gap;
+		let max = limits.max();
+		let child_limits = Limits::new(Size::ZERO, Size::new(max.width - indent, max.height));
+
+		let mut y = 0.0;
+		let mut width = 0.0f32;
+
+		let nodes = self
+			.children
+			.iter()
+			.zip(&mut tree.children)
+			.enumerate()
+			.map(|(i, (child, tree))| {
+				if i > 0 {
+					y += self.spacing;
+				}
+
+				let node = child
+					.as_widget()
+					.layout(tree, renderer, &child_limits)
+					.move_to(Point::new(indent, y));
+
+				y += node.size().height;
+				width = width.max(node.size().width);
+				node
+			})
+			.collect();
+
+		Node::with_children(
+			limits.resolve(Length::Shrink, Length::Shrink, Size::new(indent + width, y)),
+			nodes,
+		)
+	}
+
+	fn operate(
+		&self,
+		tree: &mut Tree,
+		layout: Layout<'_>,
+		renderer: &Renderer,
+		operation: &mut dyn Operation<Message>,
+	) {
+		operation.container(None, layout.bounds(), &mut |operation| {
+			self.children
+				.iter()
+				.zip(&mut tree.children)
+				.zip(layout.children())
+				.for_each(|((child, state), layout)| {
+					child
+						.as_widget()
+						.operate(state, layout, renderer, operation);
+				});
+		});
+	}
+
+	fn on_event(
+		&mut self,
+		tree: &mut Tree,
+		event: Event,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		renderer: &Renderer,
+		clipboard: &mut dyn Clipboard,
+		shell: &mut Shell<'_, Message>,
+		viewport: &Rectangle,
+	) -> event::Status {
+		self.children
+			.iter_mut()
+			.zip(&mut tree.children)
+			.zip(layout.children())
+			.map(|((child, state), layout)| {
+				child.as_widget_mut().on_event(
+					state,
+					event.clone(),
+					layout,
+					cursor,
+					renderer,
+					clipboard,
+					shell,
+					viewport,
+				)
+			})
+			.fold(event::Status::Ignored, event::Status::merge)
+	}
+
+	fn mouse_interaction(
+		&self,
+		tree: &Tree,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		viewport: &Rectangle,
+		renderer: &Renderer,
+	) -> mouse::Interaction {
+		self.children
+			.iter()
+			.zip(&tree.children)
+			.zip(layout.children())
+			.map(|((child, state), layout)| {
+				child
+					.as_widget()
+					.mouse_interaction(state, layout, cursor, viewport, renderer)
+			})
+			.max()
+			.unwrap_or_default()
+	}
+
+	fn draw(
+		&self,
+		tree: &Tree,
+		renderer: &mut Renderer,
+		theme: &Theme,
+		style: &renderer::Style,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		viewport: &Rectangle,
+	) {
+		let bounds = layout.bounds();
+		let last = self.children.len().saturating_sub(1);
+		let mid_width = self.width * Branch::MID_RATIO;
+
+		for (i, child_layout) in layout.children().enumerate() {
+			let child = child_layout.bounds();
+			let top = child.y;
+			let bottom = child.y + child.height + if i < last { self.spacing } else { 0.0 };
+
+			let corners = (i == 0) as u8 + (i == last) as u8;
+			let corner = if corners == 0 {
+				0.0
+			} else {
+				self.width.min(child.height / corners as f32)
+			};
+
+			let mut mid_top = top;
+			let mut mid_bottom = bottom;
+
+			if i == 0 {
+				renderer.draw(
+					Branch::Top.handle(),
+					Some(self.color),
+					Rectangle::new(Point::new(bounds.x, top), Size::new(corner, corner)),
+				);
+				mid_top += corner;
+			}
+
+			if i == last {
+				renderer.draw(
+					Branch::Bottom.handle(),
+					Some(self.color),
+					Rectangle::new(
+						Point::new(bounds.x, bottom - corner),
+						Size::new(corner, corner),
+					),
+				);
+				mid_bottom -= corner;
+			}
+
+			if mid_bottom > mid_top {
+				renderer.draw(
+					Branch::Mid.handle(),
+					Some(self.color),
+					Rectangle::new(
+						Point::new(bounds.x, mid_top),
+						Size::new(mid_width, mid_bottom - mid_top),
+					),
+				);
+			}
+		}
+
+		for ((child, state), layout) in self
+			.children
+			.iter()
+			.zip(&tree.children)
+			.zip(layout.children())
+		{
+			child
+				.as_widget()
+				.draw(state, renderer, theme, style, layout, cursor, viewport);
+		}
+	}
+
+	fn overlay<'b>(
+		&'b mut self,
+		tree: &'b mut Tree,
+		layout: Layout<'_>,
+		renderer: &Renderer,
+		translation: Vector,
+	) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+		overlay::from_children(&mut self.children, tree, layout, renderer, translation)
+	}
+}
+
+impl<'a, Message, Theme, Renderer> From<Gutter<'a, Message, Theme, Renderer>>
+	for Element<'a, Message, Theme, Renderer>
+where
+	Message: 'a,
+	Theme: 'a,
+	Renderer: svg::Renderer + 'a,
+{
+	fn from(gutter: Gutter<'a, Message, Theme, Renderer>) -> Self {
+		Self::new(gutter)
+	}
+}
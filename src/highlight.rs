@@ -0,0 +1,100 @@
+//! Color nodes by what they mean rather than how they're written.
+use crate::binding;
+use crate::code::{Code, Node, NodeId};
+use crate::theme::Theme;
+use crate::types::UNIQUE_VALUES;
+use iced::Color;
+use std::collections::{HashMap, HashSet};
+
+/// What a node means in the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+	/// The name a binding introduces.
+	Definition,
+	/// A use of a definition.
+	Reference,
+	/// A use of a definition that gives it a new value.
+	Mutation,
+	/// A value there is only one of, like `Console`.
+	Unique,
+	/// A conversion from one type to another, like `(Str -> Int)`.
+	Converter,
+	Literal,
+}
+
+impl Category {
+	pub fn color(self, theme: &Theme) -> Color {
+		match self {
+			Category::Definition => theme.semantic.definition,
+			Category::Reference => theme.semantic.reference,
+			Category::Mutation => theme.semantic.mutation,
+			Category::Unique => theme.semantic.unique,
+			Category::Converter => theme.semantic.converter,
+			Category::Literal => theme.semantic.literal,
+		}
+	}
+}
+
+/// The category of every highlighted node.
+pub type Highlights = HashMap<NodeId, Category>;
+
+/// Classifies nodes, highlighting only the categories that are switched on.
+#[derive(Debug, Default)]
+pub struct Highlighter {
+	disabled: HashSet<Category>,
+}
+
+impl Highlighter {
+	pub fn is_enabled(&self, category: Category) -> bool {
+		!self.disabled.contains(&category)
+	}
+
+	pub fn toggle(&mut self, category: Category) {
+		if !self.disabled.remove(&category) {
+			self.disabled.insert(category);
+		}
+	}
+
+	pub fn highlight(&self, code: &Code) -> Highlights {
+		let mut highlights = classify(code);
+		highlights.retain(|_, &mut category| self.is_enabled(category));
+		highlights
+	}
+}
+
+/// Classifies every node of `code` by what kind of node it is and the role
+/// it plays in its group.
+pub fn classify(code: &Code) -> Highlights {
+	let mut highlights = Highlights::new();
+
+	for node_id in code.walk(code.root()) {
+		let Some(placed) = code.get(node_id) else {
+			continue;
+		};
+
+		let category = match &placed.node {
+			Node::Group(_) => {
+				if let Some(reference) = binding::mutated(code, node_id) {
+					highlights.insert(reference, Category::Mutation);
+				}
+				None
+			}
+			Node::Definition(_) => Some(Category::Definition),
+			Node::Reference(_) | Node::Member { .. } => Some(Category::Reference),
+			Node::Text(_) | Node::Operator(_) => None,
+			Node::Type(name) if UNIQUE_VALUES.contains(&name.as_str()) => Some(Category::Unique),
+			Node::Type(_) => None,
+			Node::Converter { .. } => Some(Category::Converter),
+			Node::Import(_) | Node::Implicit(_) => Some(Category::Definition),
+			Node::Literal(_) | Node::Pixel { .. } => Some(Category::Literal),
+			// Aliases show their node with its own highlight.
+			Node::Alias(_) => None,
+		};
+
+		if let Some(category) = category {
+			highlights.entry(node_id).or_insert(category);
+		}
+	}
+
+	highlights
+}
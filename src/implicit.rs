@@ -0,0 +1,109 @@
+//! Give functions inputs like `Console` from the context they're used in
+//! instead of at every use.
+use crate::binding::is_name;
+use crate::code::{Code, Node, NodeId};
+use crate::types::{self, Provider, ResolveError};
+use std::collections::HashMap;
+
+/// The values filling in the implicit inputs of calls by call, with the type
+/// each one is required to have.
+pub type Calls = HashMap<NodeId, Vec<(String, Result<Provider, ResolveError>)>>;
+
+/// The node a text node should become once it's typed: an implicit input if
+/// it names a type like `@Console`.
+pub fn declare(code: &Code, node_id: NodeId) -> Option<Node> {
+	let Node::Text(string) = &code.get(node_id)?.node else {
+		return None;
+	};
+
+	let name = string.trim().strip_prefix('@')?;
+	is_name(name).then(|| Node::Implicit(name.into()))
+}
+
+/// The types of the implicit inputs a function declares anywhere in its
+/// binding, leaving out the ones of the functions inside it.
+pub fn parameters(code: &Code, function: NodeId) -> Vec<&str> {
+	let Some(binding) = code.get(function).and_then(|placed| placed.parent()) else {
+		return Vec::new();
+	};
+
+	let mut parameters = Vec::new();
+	let mut stack = vec![binding];
+	while let Some(node_id) = stack.pop() {
+		let Some(placed) = code.get(node_id) else {
+			continue;
+		};
+
+		match &placed.node {
+			Node::Implicit(required) => parameters.push(required.as_str()),
+			Node::Group(children) => {
+				let is_binding = children.first().is_some_and(|&first| {
+					first != function
+						&& matches!(
+							code.get(first).map(|placed| &placed.node),
+							Some(Node::Definition(_))
+						)
+				});
+				if !is_binding {
+					stack.extend(children.iter().rev());
+				}
+			}
+			_ => {}
+		}
+	}
+
+	parameters
+}
+
+/// The types of the implicit inputs a node gets from the bindings it's in,
+/// or `None` on the lines outside of any binding, where the unique values
+/// are available instead.
+pub fn context(code: &Code, node_id: NodeId) -> Option<Vec<&str>> {
+	let mut functions = code
+		.path(node_id)
+		.into_iter()
+		.filter_map(|binding| {
+			let &definition = code.children(binding).first()?;
+			matches!(code.get(definition)?.node, Node::Definition(_)).then_some(definition)
+		})
+		.peekable();
+
+	functions.peek()?;
+	Some(
+		functions
+			.flat_map(|function| parameters(code, function))
+			.collect(),
+	)
+}
+
+/// The values filling in the implicit inputs of the function a reference
+/// calls, found where it's called: the implicit inputs of the bindings
+/// around the call, or the unique values outside of any binding.
+pub fn arguments(code: &Code, call: NodeId) -> Vec<(&str, Result<Provider, ResolveError>)> {
+	let Some(Node::Reference(function)) = code.get(call).map(|placed| &placed.node) else {
+		return Vec::new();
+	};
+
+	parameters(code, *function)
+		.into_iter()
+		.map(|required| (required, types::resolve(code, call, required)))
+		.collect()
+}
+
+/// The values filling in the implicit inputs of every call to a function
+/// that has any, by call.
+pub fn calls(code: &Code) -> Calls {
+	code.walk(code.root())
+		.into_iter()
+		.filter_map(|call| {
+			let arguments = arguments(code, call);
+			(!arguments.is_empty()).then(|| {
+				let arguments = arguments
+					.into_iter()
+					.map(|(required, provider)| (required.to_owned(), provider))
+					.collect();
+				(call, arguments)
+			})
+		})
+		.collect()
+}
@@ -0,0 +1,746 @@
+//! Map key chords and sequences of them to editor actions.
+use crate::code::Aspect;
+use crate::config;
+use crate::highlight::Category;
+use crate::mode::Mode;
+use iced::keyboard::{key::Named, Key, Modifiers};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/// The bindings used when the user config doesn't override them.
+///
+/// Bindings before the first `[mode]` header belong to normal mode.
+const DEFAULT_KEYMAP: &str = "\
+[normal]
+up = select_previous
+down = select_next
+left = select_parent
+right = select_first_child
+k = select_previous
+j = select_next
+h = select_parent
+l = select_first_child
+g g = select_root
+escape = clear_selection
+i = insert_mode
+v = select_mode
+x = delete_node
+y = copy_alias
+p = paste_alias
+g d = go_to_definition
+z s = cycle_scope
+z t = toggle_tail_dedent
+z b = toggle_dedent
+z i = toggle_implicit_arguments
+g w = wrap_ambiguous
+ctrl+p = open_palette
+ctrl+t = next_theme
+u = undo
+ctrl+r = redo
+ctrl+n = new_document
+ctrl+s = save
+ctrl+w = close_document
+ctrl+tab = next_document
+ctrl+shift+tab = previous_document
+z d = toggle_docs
+z n = toggle_notes
+z m = toggle_memory_management
+z e = toggle_error_handling
+
+[insert]
+escape = normal_mode
+backspace = delete_backward
+tab = complete
+ctrl+p = open_palette
+ctrl+s = save
+ctrl+tab = next_document
+ctrl+shift+tab = previous_document
+
+[select]
+up = select_previous
+down = select_next
+k = select_previous
+j = select_next
+escape = normal_mode
+v = normal_mode
+ctrl+p = open_palette
+";
+
+/// Names of the non-character keys that can be used in keymap files.
+const NAMED_KEYS: &[(&str, Named)] = &[
+	("up", Named::ArrowUp),
+	("down", Named::ArrowDown),
+	("left", Named::ArrowLeft),
+	("right", Named::ArrowRight),
+	("enter", Named::Enter),
+	("escape", Named::Escape),
+	("tab", Named::Tab),
+	("space", Named::Space),
+	("backspace", Named::Backspace),
+	("delete", Named::Delete),
+	("home", Named::Home),
+	("end", Named::End),
+	("pageup", Named::PageUp),
+	("pagedown", Named::PageDown),
+	("f1", Named::F1),
+	("f2", Named::F2),
+	("f3", Named::F3),
+	("f4", Named::F4),
+	("f5", Named::F5),
+	("f6", Named::F6),
+	("f7", Named::F7),
+	("f8", Named::F8),
+	("f9", Named::F9),
+	("f10", Named::F10),
+	("f11", Named::F11),
+	("f12", Named::F12),
+];
+
+/// A named editor action that can be bound to keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+	SelectPrevious,
+	SelectNext,
+	SelectParent,
+	SelectFirstChild,
+	SelectRoot,
+	ClearSelection,
+	NormalMode,
+	InsertMode,
+	SelectMode,
+	DeleteBackward,
+	OpenPalette,
+	NextTheme,
+	ReloadTheme,
+	ToggleHighlight(Category),
+	/// Shows, dims or hides an aspect of the code in turn.
+	CycleAspect(Aspect),
+	Undo,
+	Redo,
+	NewDocument,
+	/// Writes the document to its file as text.
+	Save,
+	/// Closes the document, asking first if it has unsaved changes.
+	CloseDocument,
+	NextDocument,
+	PreviousDocument,
+	DeleteNode,
+	GoToDefinition,
+	/// Turns a group into an additive, overwriting or isolating context, or
+	/// back into a plain group, in turn.
+	CycleScope,
+	/// Completes the name being typed to the first available one.
+	Complete,
+	/// Wraps operators that can't be mixed in a group of their own.
+	WrapAmbiguous,
+	/// Shows the blocks of the document that reach to the end of their group
+	/// at the group's indentation, or each at its own.
+	ToggleTailDedent,
+	/// Keeps the selected tail block at its own indentation, or dedents it
+	/// again.
+	ToggleDedent,
+	/// Shows what fills in the implicit inputs at every call, or hides it.
+	ToggleImplicitArguments,
+	/// Remembers the selected node to show elsewhere.
+	CopyAlias,
+	/// Shows the copied node after the selection as well, through an alias.
+	PasteAlias,
+}
+
+impl Action {
+	pub const ALL: &'static [Action] = &[
+		Action::SelectPrevious,
+		Action::SelectNext,
+		Action::SelectParent,
+		Action::SelectFirstChild,
+		Action::SelectRoot,
+		Action::ClearSelection,
+		Action::NormalMode,
+		Action::InsertMode,
+		Action::SelectMode,
+		Action::DeleteBackward,
+		Action::OpenPalette,
+		Action::NextTheme,
+		Action::ReloadTheme,
+		Action::ToggleHighlight(Category::Definition),
+		Action::ToggleHighlight(Category::Reference),
+		Action::ToggleHighlight(Category::Mutation),
+		Action::ToggleHighlight(Category::Unique),
+		Action::ToggleHighlight(Category::Converter),
+		Action::ToggleHighlight(Category::Literal),
+		Action::CycleAspect(Aspect::Docs),
+		Action::CycleAspect(Aspect::Notes),
+		Action::CycleAspect(Aspect::MemoryManagement),
+		Action::CycleAspect(Aspect::ErrorHandling),
+		Action::Undo,
+		Action::Redo,
+		Action::NewDocument,
+		Action::Save,
+		Action::CloseDocument,
+		Action::NextDocument,
+		Action::PreviousDocument,
+		Action::DeleteNode,
+		Action::GoToDefinition,
+		Action::CycleScope,
+		Action::Complete,
+		Action::WrapAmbiguous,
+		Action::ToggleTailDedent,
+		Action::ToggleDedent,
+		Action::ToggleImplicitArguments,
+		Action::CopyAlias,
+		Action::PasteAlias,
+	];
+
+	/// The name the action is referred to by in keymap files.
+	pub fn name(self) -> &'static str {
+		match self {
+			Action::SelectPrevious => "select_previous",
+			Action::SelectNext => "select_next",
+			Action::SelectParent => "select_parent",
+			Action::SelectFirstChild => "select_first_child",
+			Action::SelectRoot => "select_root",
+			Action::ClearSelection => "clear_selection",
+			Action::NormalMode => "normal_mode",
+			Action::InsertMode => "insert_mode",
+			Action::SelectMode => "select_mode",
+			Action::DeleteBackward => "delete_backward",
+			Action::OpenPalette => "open_palette",
+			Action::NextTheme => "next_theme",
+			Action::ReloadTheme => "reload_theme",
+			Action::ToggleHighlight(Category::Definition) => "toggle_definitions",
+			Action::ToggleHighlight(Category::Reference) => "toggle_references",
+			Action::ToggleHighlight(Category::Mutation) => "toggle_mutations",
+			Action::ToggleHighlight(Category::Unique) => "toggle_unique_values",
+			Action::ToggleHighlight(Category::Converter) => "toggle_converters",
+			Action::ToggleHighlight(Category::Literal) => "toggle_literals",
+			Action::CycleAspect(Aspect::Docs) => "toggle_docs",
+			Action::CycleAspect(Aspect::Notes) => "toggle_notes",
+			Action::CycleAspect(Aspect::MemoryManagement) => "toggle_memory_management",
+			Action::CycleAspect(Aspect::ErrorHandling) => "toggle_error_handling",
+			Action::Undo => "undo",
+			Action::Redo => "redo",
+			Action::NewDocument => "new_document",
+			Action::Save => "save",
+			Action::CloseDocument => "close_document",
+			Action::NextDocument => "next_document",
+			Action::PreviousDocument => "previous_document",
+			Action::DeleteNode => "delete_node",
+			Action::GoToDefinition => "go_to_definition",
+			Action::CycleScope => "cycle_scope",
+			Action::Complete => "complete",
+			Action::WrapAmbiguous => "wrap_ambiguous",
+			Action::ToggleTailDedent => "toggle_tail_dedent",
+			Action::ToggleDedent => "toggle_dedent",
+			Action::ToggleImplicitArguments => "toggle_implicit_arguments",
+			Action::CopyAlias => "copy_alias",
+			Action::PasteAlias => "paste_alias",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Action> {
+		Action::ALL
+			.iter()
+			.copied()
+			.find(|action| action.name() == name)
+	}
+}
+
+/// A single key pressed together with some modifiers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chord {
+	key: Key,
+	modifiers: Modifiers,
+}
+
+impl Chord {
+	/// Creates a [`Chord`] from a key press, or `None` for modifier keys
+	/// which only ever take part in other chords.
+	pub fn from_press(key: &Key, modifiers: Modifiers) -> Option<Chord> {
+		let key = match key.as_ref() {
+			Key::Named(Named::Shift | Named::Control | Named::Alt | Named::Super | Named::Meta)
+			| Key::Unidentified => return None,
+			Key::Named(named) => Key::Named(named),
+			Key::Character(c) => return Some(Chord::character(c, modifiers)),
+		};
+
+		Some(Chord { key, modifiers })
+	}
+
+	/// A character key pressed with some modifiers. Shift only counts for
+	/// letters, since it's already part of symbols like `:`.
+	fn character(c: &str, mut modifiers: Modifiers) -> Chord {
+		let lowercase = c.to_lowercase();
+		if lowercase == c.to_uppercase() {
+			modifiers.remove(Modifiers::SHIFT);
+		}
+
+		Chord {
+			key: Key::Character(lowercase.into()),
+			modifiers,
+		}
+	}
+
+	/// Parses a chord like `ctrl+shift+k`.
+	fn parse(chord: &str) -> Option<Chord> {
+		let mut parts = chord.split('+').collect::<Vec<_>>();
+		let key = parts.pop()?;
+		let mut modifiers = Modifiers::empty();
+
+		for part in parts {
+			modifiers |= match part.to_lowercase().as_str() {
+				"shift" => Modifiers::SHIFT,
+				"ctrl" | "control" => Modifiers::CTRL,
+				"alt" => Modifiers::ALT,
+				"logo" | "super" | "cmd" => Modifiers::LOGO,
+				_ => return None,
+			};
+		}
+
+		let mut chars = key.chars();
+		if let (Some(c), None) = (chars.next(), chars.next()) {
+			if c.is_uppercase() {
+				modifiers |= Modifiers::SHIFT;
+			}
+			return Some(Chord::character(key, modifiers));
+		}
+
+		let key = NAMED_KEYS
+			.iter()
+			.find(|(name, _)| name.eq_ignore_ascii_case(key))
+			.map(|&(_, named)| Key::Named(named))?;
+
+		Some(Chord { key, modifiers })
+	}
+}
+
+impl fmt::Display for Chord {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (modifier, name) in [
+			(Modifiers::CTRL, "ctrl"),
+			(Modifiers::ALT, "alt"),
+			(Modifiers::LOGO, "logo"),
+			(Modifiers::SHIFT, "shift"),
+		] {
+			if self.modifiers.contains(modifier) {
+				write!(f, "{name}+")?;
+			}
+		}
+
+		match &self.key {
+			Key::Character(c) => write!(f, "{c}"),
+			Key::Named(named) => match NAMED_KEYS.iter().find(|(_, key)| key == named) {
+				Some((name, _)) => write!(f, "{name}"),
+				None => write!(f, "{}", format!("{named:?}").to_lowercase()),
+			},
+			Key::Unidentified => write!(f, "?"),
+		}
+	}
+}
+
+/// A sequence of chords pressed one after another.
+pub type Sequence = Vec<Chord>;
+
+/// Something wrong with a keymap, found while loading it.
+#[derive(Debug)]
+pub enum KeymapError {
+	Unreadable(PathBuf, std::io::Error),
+	Malformed {
+		source: String,
+		line: usize,
+	},
+	UnknownMode {
+		source: String,
+		line: usize,
+		name: String,
+	},
+	UnknownKey {
+		source: String,
+		line: usize,
+		key: String,
+	},
+	UnknownAction {
+		source: String,
+		line: usize,
+		name: String,
+	},
+	/// The same sequence is bound twice in the same mode of the same file.
+	Duplicate {
+		source: String,
+		line: usize,
+		sequence: String,
+	},
+	/// One sequence starts with another, so the longer one can never be typed.
+	Shadowed {
+		mode: Mode,
+		shorter: String,
+		longer: String,
+	},
+}
+
+impl fmt::Display for KeymapError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			KeymapError::Unreadable(path, error) => {
+				write!(f, "cannot read {}: {error}", path.display())
+			}
+			KeymapError::Malformed { source, line } => {
+				write!(f, "{source}:{line}: expected `<keys> = <action>`")
+			}
+			KeymapError::UnknownMode { source, line, name } => {
+				write!(f, "{source}:{line}: unknown mode `{name}`")
+			}
+			KeymapError::UnknownKey { source, line, key } => {
+				write!(f, "{source}:{line}: unknown key `{key}`")
+			}
+			KeymapError::UnknownAction { source, line, name } => {
+				write!(f, "{source}:{line}: unknown action `{name}`")
+			}
+			KeymapError::Duplicate {
+				source,
+				line,
+				sequence,
+			} => write!(f, "{source}:{line}: `{sequence}` is already bound"),
+			KeymapError::Shadowed {
+				mode,
+				shorter,
+				longer,
+			} => write!(
+				f,
+				"`{longer}` can never be typed in {} mode because `{shorter}` is bound",
+				mode.name()
+			),
+		}
+	}
+}
+
+/// Bindings from key sequences to [`Action`]s in every [`Mode`].
+type Bindings = HashMap<Mode, HashMap<Sequence, Action>>;
+
+/// Bindings from key sequences to [`Action`]s together with the chords typed
+/// so far.
+#[derive(Debug, Default)]
+pub struct Keymap {
+	bindings: Bindings,
+	pending: Sequence,
+}
+
+impl Keymap {
+	/// Loads the default bindings, overridden by the ones in the user config
+	/// file if there is one.
+	pub fn load() -> (Keymap, Vec<KeymapError>) {
+		let mut errors = Vec::new();
+		let mut bindings = parse("defaults", DEFAULT_KEYMAP, &mut errors);
+
+		if let Some(path) = config::path("BISMUTH_KEYMAP", "keymap") {
+			match std::fs::read_to_string(&path) {
+				Ok(text) => {
+					for (mode, overrides) in parse(&path.display().to_string(), &text, &mut errors)
+					{
+						bindings.entry(mode).or_default().extend(overrides);
+					}
+				}
+				Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+				Err(error) => errors.push(KeymapError::Unreadable(path, error)),
+			}
+		}
+
+		unshadow(&mut bindings, &mut errors);
+
+		(
+			Keymap {
+				bindings,
+				pending: Vec::new(),
+			},
+			errors,
+		)
+	}
+
+	/// Feeds a chord pressed in the given [`Mode`] to the keymap, returning
+	/// the bound [`Action`] once a whole sequence has been typed.
+	pub fn press(&mut self, mode: Mode, chord: Chord) -> Option<Action> {
+		let bindings = self.bindings.get(&mode)?;
+		self.pending.push(chord);
+
+		if let Some(&action) = bindings.get(&self.pending) {
+			self.pending.clear();
+			return Some(action);
+		}
+
+		if self.is_pending_prefix(mode) {
+			return None;
+		}
+
+		// The typed sequence leads nowhere, but its last chord may start
+		// another one.
+		let chord = self.pending.pop()?;
+		let retry = !self.pending.is_empty();
+		self.pending.clear();
+
+		if retry {
+			self.press(mode, chord)
+		} else {
+			None
+		}
+	}
+
+	/// Whether the chords typed so far start a longer sequence.
+	pub fn is_pending(&self) -> bool {
+		!self.pending.is_empty()
+	}
+
+	/// Forgets the chords typed so far, such as when the mode changes.
+	pub fn reset(&mut self) {
+		self.pending.clear();
+	}
+
+	/// Returns the sequences bound to the [`Action`] in the given [`Mode`].
+	pub fn sequences(&self, mode: Mode, action: Action) -> Vec<String> {
+		let mut sequences = self
+			.bindings
+			.get(&mode)
+			.into_iter()
+			.flatten()
+			.filter(|(_, &bound)| bound == action)
+			.map(|(sequence, _)| display(sequence))
+			.collect::<Vec<_>>();
+		sequences.sort();
+		sequences
+	}
+
+	fn is_pending_prefix(&self, mode: Mode) -> bool {
+		self.bindings.get(&mode).is_some_and(|bindings| {
+			bindings
+				.keys()
+				.any(|sequence| sequence.starts_with(&self.pending))
+		})
+	}
+}
+
+fn parse(source: &str, text: &str, errors: &mut Vec<KeymapError>) -> Bindings {
+	let mut bindings = Bindings::new();
+	let mut mode = Mode::Normal;
+
+	for (i, line) in text.lines().enumerate() {
+		let line_number = i + 1;
+		let line = line.split('#').next().unwrap_or_default().trim();
+
+		if line.is_empty() {
+			continue;
+		}
+
+		if let Some(name) = line
+			.strip_prefix('[')
+			.and_then(|line| line.strip_suffix(']'))
+		{
+			match Mode::from_name(name.trim()) {
+				Some(named) => mode = named,
+				None => errors.push(KeymapError::UnknownMode {
+					source: source.into(),
+					line: line_number,
+					name: name.trim().into(),
+				}),
+			}
+			continue;
+		}
+
+		let Some((keys, name)) = line.split_once('=') else {
+			errors.push(KeymapError::Malformed {
+				source: source.into(),
+				line: line_number,
+			});
+			continue;
+		};
+
+		let sequence = keys
+			.split_whitespace()
+			.map(|key| {
+				Chord::parse(key).ok_or_else(|| KeymapError::UnknownKey {
+					source: source.into(),
+					line: line_number,
+					key: key.into(),
+				})
+			})
+			.collect::<Result<Sequence, _>>();
+
+		let sequence = match sequence {
+			Ok(sequence) if !sequence.is_empty() => sequence,
+			Ok(_) => {
+				errors.push(KeymapError::Malformed {
+					source: source.into(),
+					line: line_number,
+				});
+				continue;
+			}
+			Err(error) => {
+				errors.push(error);
+				continue;
+			}
+		};
+
+		let Some(action) = Action::from_name(name.trim()) else {
+			errors.push(KeymapError::UnknownAction {
+				source: source.into(),
+				line: line_number,
+				name: name.trim().into(),
+			});
+			continue;
+		};
+
+		let bindings = bindings.entry(mode).or_default();
+
+		if bindings.contains_key(&sequence) {
+			errors.push(KeymapError::Duplicate {
+				source: source.into(),
+				line: line_number,
+				sequence: display(&sequence),
+			});
+			continue;
+		}
+
+		bindings.insert(sequence, action);
+	}
+
+	bindings
+}
+
+/// Drops the sequences that start with another one bound in the same mode,
+/// since typing the shorter one always runs its action first.
+fn unshadow(bindings: &mut Bindings, errors: &mut Vec<KeymapError>) {
+	for (&mode, bindings) in bindings {
+		let mut sequences = bindings.keys().cloned().collect::<Vec<_>>();
+		sequences.sort_by_key(Vec::len);
+
+		for (i, shorter) in sequences.iter().enumerate() {
+			for longer in &sequences[i + 1..] {
+				if longer.len() > shorter.len()
+					&& longer.starts_with(shorter)
+					&& bindings.remove(longer).is_some()
+				{
+					errors.push(KeymapError::Shadowed {
+						mode,
+						shorter: display(shorter),
+						longer: display(longer),
+					});
+				}
+			}
+		}
+	}
+}
+
+fn display(sequence: &[Chord]) -> String {
+	sequence
+		.iter()
+		.map(Chord::to_string)
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn chord(text: &str) -> Chord {
+		Chord::parse(text).unwrap_or_else(|| panic!("unknown key {text:?}"))
+	}
+
+	fn press(c: &str, modifiers: Modifiers) -> Chord {
+		Chord::from_press(&Key::Character(c.into()), modifiers).unwrap()
+	}
+
+	/// The keymap bound in `text` alone, with the errors found in it.
+	fn keymap(text: &str) -> (Keymap, Vec<KeymapError>) {
+		let mut errors = Vec::new();
+		let mut bindings = parse("test", text, &mut errors);
+		unshadow(&mut bindings, &mut errors);
+		let keymap = Keymap {
+			bindings,
+			pending: Vec::new(),
+		};
+		(keymap, errors)
+	}
+
+	#[test]
+	fn defaults() {
+		let (_, errors) = keymap(DEFAULT_KEYMAP);
+		assert!(errors.is_empty(), "{}", errors[0]);
+	}
+
+	#[test]
+	fn chords() {
+		assert_eq!(chord("ctrl+shift+k").to_string(), "ctrl+shift+k");
+		assert_eq!(chord("Control+Tab").to_string(), "ctrl+tab");
+		assert_eq!(chord("K"), chord("shift+k"));
+		assert_eq!(chord("F5").key, Key::Named(Named::F5));
+		// Shift is already part of the symbol.
+		assert_eq!(chord("shift+:"), chord(":"));
+		assert_eq!(chord(":").to_string(), ":");
+
+		assert_eq!(Chord::parse("hyper+k"), None);
+		assert_eq!(Chord::parse("ctrl+"), None);
+		assert_eq!(Chord::parse("kk"), None);
+	}
+
+	#[test]
+	fn presses() {
+		assert_eq!(press("k", Modifiers::CTRL), chord("ctrl+k"));
+		assert_eq!(press("K", Modifiers::SHIFT), chord("K"));
+		assert_eq!(press(":", Modifiers::SHIFT), chord(":"));
+		assert_eq!(
+			press("?", Modifiers::SHIFT | Modifiers::CTRL),
+			chord("ctrl+?")
+		);
+		assert_eq!(
+			Chord::from_press(&Key::Named(Named::Shift), Modifiers::SHIFT),
+			None
+		);
+	}
+
+	#[test]
+	fn sequences() {
+		let (mut keymap, errors) = keymap("g g = select_root\n: = open_palette\nj = select_next\n");
+		assert!(errors.is_empty());
+		let mode = Mode::Normal;
+
+		assert_eq!(
+			keymap.press(mode, press(":", Modifiers::SHIFT)),
+			Some(Action::OpenPalette)
+		);
+		assert_eq!(keymap.press(mode, chord("g")), None);
+		assert!(keymap.is_pending());
+		assert_eq!(keymap.press(mode, chord("g")), Some(Action::SelectRoot));
+		assert!(!keymap.is_pending());
+
+		// A chord that leads nowhere starts over from itself.
+		assert_eq!(keymap.press(mode, chord("g")), None);
+		assert_eq!(keymap.press(mode, chord("j")), Some(Action::SelectNext));
+		assert_eq!(keymap.press(Mode::Insert, chord("j")), None);
+	}
+
+	#[test]
+	fn duplicate() {
+		let (keymap, errors) =
+			keymap("j = select_next\n[insert]\nj = select_next\n[normal]\nj = undo\n");
+		assert!(matches!(
+			&errors[..],
+			[KeymapError::Duplicate { line: 5, sequence, .. }] if sequence == "j"
+		));
+		// The first binding stays.
+		assert_eq!(keymap.sequences(Mode::Normal, Action::SelectNext), ["j"]);
+		assert_eq!(keymap.sequences(Mode::Insert, Action::SelectNext), ["j"]);
+	}
+
+	#[test]
+	fn shadowed() {
+		let (keymap, errors) = keymap("g = select_root\ng d = go_to_definition\n");
+		assert!(matches!(
+			&errors[..],
+			[KeymapError::Shadowed { mode: Mode::Normal, shorter, longer }]
+				if shorter == "g" && longer == "g d"
+		));
+		assert!(keymap
+			.sequences(Mode::Normal, Action::GoToDefinition)
+			.is_empty());
+		assert_eq!(keymap.sequences(Mode::Normal, Action::SelectRoot), ["g"]);
+	}
+}
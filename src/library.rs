@@ -0,0 +1,268 @@
+//! Refer to the items of external libraries by short names like
+//! `library/Item`, leaving out the modules in between.
+use crate::binding::is_name;
+use crate::code::{Code, Node, NodeId};
+use crate::config;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+
+/// The libraries available without a manifest of their own.
+///
+/// Each `[library]` header is followed by the full paths of the items it
+/// exports, one per line.
+const DEFAULT_MANIFEST: &str = "\
+[std]
+collections/HashMap
+collections/HashSet
+collections/hash_map/Entry
+collections/btree_map/Entry
+fmt/Display
+fmt/Error
+io/Error
+io/stdin
+";
+
+/// A library and the full paths of the items it exports.
+#[derive(Debug, Clone)]
+struct Library {
+	name: String,
+	items: Vec<String>,
+}
+
+/// Something wrong with a library manifest, found while loading it.
+#[derive(Debug)]
+pub enum LibraryError {
+	Unreadable(PathBuf, std::io::Error),
+	OutsideLibrary {
+		source: String,
+		line: usize,
+	},
+	InvalidPath {
+		source: String,
+		line: usize,
+		path: String,
+	},
+}
+
+impl fmt::Display for LibraryError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			LibraryError::Unreadable(path, error) => {
+				write!(f, "cannot read {}: {error}", path.display())
+			}
+			LibraryError::OutsideLibrary { source, line } => {
+				write!(f, "{source}:{line}: expected a `[library]` header first")
+			}
+			LibraryError::InvalidPath { source, line, path } => {
+				write!(
+					f,
+					"{source}:{line}: `{path}` is not a path like `module/Item`"
+				)
+			}
+		}
+	}
+}
+
+/// Why a short name doesn't refer to a single item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportError {
+	UnknownLibrary(String),
+	UnknownItem {
+		library: String,
+		item: String,
+	},
+	/// Several items of the library have the same name.
+	Collision {
+		short: String,
+		paths: Vec<String>,
+	},
+}
+
+impl fmt::Display for ImportError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ImportError::UnknownLibrary(library) => write!(f, "no library named `{library}`"),
+			ImportError::UnknownItem { library, item } => {
+				write!(f, "`{library}` exports no `{item}`")
+			}
+			ImportError::Collision { short, paths } => {
+				write!(f, "`{short}` could be any of {}", paths.join(", "))
+			}
+		}
+	}
+}
+
+/// The libraries items can be imported from.
+#[derive(Debug, Default)]
+pub struct Libraries {
+	all: Vec<Library>,
+}
+
+impl Libraries {
+	/// Loads the default libraries together with the ones in the user's
+	/// manifest, which replace those of the same name.
+	pub fn load() -> (Libraries, Vec<LibraryError>) {
+		let mut libraries = Libraries::default();
+		let mut errors = Vec::new();
+		parse(
+			"defaults",
+			DEFAULT_MANIFEST,
+			&mut libraries.all,
+			&mut errors,
+		);
+
+		if let Some(path) = config::path("BISMUTH_LIBRARIES", "libraries") {
+			match std::fs::read_to_string(&path) {
+				Ok(text) => parse(
+					&path.display().to_string(),
+					&text,
+					&mut libraries.all,
+					&mut errors,
+				),
+				Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+				Err(error) => errors.push(LibraryError::Unreadable(path, error)),
+			}
+		}
+
+		(libraries, errors)
+	}
+
+	/// The full path of the item a short name like `library/Item` refers to.
+	pub fn resolve(&self, short: &str) -> Result<String, ImportError> {
+		let (library, item) = short.split_once('/').unwrap_or((short, ""));
+		let Some(found) = self.all.iter().find(|found| found.name == library) else {
+			return Err(ImportError::UnknownLibrary(library.into()));
+		};
+
+		let paths = found
+			.items
+			.iter()
+			.filter(|path| item_name(path) == item)
+			.map(|path| format!("{library}/{path}"))
+			.collect::<Vec<_>>();
+
+		match &paths[..] {
+			[] => Err(ImportError::UnknownItem {
+				library: library.into(),
+				item: item.into(),
+			}),
+			[path] => Ok(path.clone()),
+			_ => Err(ImportError::Collision {
+				short: short.into(),
+				paths,
+			}),
+		}
+	}
+
+	/// The short names of the items that start with what was typed into a
+	/// node, sorted.
+	pub fn completions(&self, code: &Code, node_id: NodeId) -> Vec<String> {
+		let typed = match code.get(node_id).map(|placed| &placed.node) {
+			Some(Node::Text(typed)) => typed.trim(),
+			_ => return Vec::new(),
+		};
+
+		let mut names = self
+			.all
+			.iter()
+			.flat_map(|library| {
+				library
+					.items
+					.iter()
+					.map(|path| format!("{}/{}", library.name, item_name(path)))
+			})
+			.filter(|name| name.starts_with(typed) && name != typed)
+			.collect::<HashSet<_>>()
+			.into_iter()
+			.collect::<Vec<_>>();
+		names.sort_unstable();
+		names
+	}
+
+	/// The import nodes that don't refer to a single item.
+	pub fn unresolved(&self, code: &Code) -> HashSet<NodeId> {
+		code.walk(code.root())
+			.into_iter()
+			.filter(
+				|&node_id| match code.get(node_id).map(|placed| &placed.node) {
+					Some(Node::Import(short)) => self.resolve(short).is_err(),
+					_ => false,
+				},
+			)
+			.collect()
+	}
+}
+
+/// The node a text node should become once it's typed: an import if it's a
+/// short name like `library/Item`.
+pub fn import(code: &Code, node_id: NodeId) -> Option<Node> {
+	let Node::Text(string) = &code.get(node_id)?.node else {
+		return None;
+	};
+
+	let (library, item) = string.trim().split_once('/')?;
+	(is_name(library) && is_name(item)).then(|| Node::Import(string.trim().into()))
+}
+
+/// The last part of a path, which the item is referred to by.
+fn item_name(path: &str) -> &str {
+	path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Parses the libraries in `text` into `libraries`, replacing the ones with
+/// the same name.
+fn parse(source: &str, text: &str, libraries: &mut Vec<Library>, errors: &mut Vec<LibraryError>) {
+	let mut current = None;
+
+	for (i, line) in text.lines().enumerate() {
+		let line_number = i + 1;
+		let line = line.trim();
+
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+
+		if let Some(name) = line
+			.strip_prefix('[')
+			.and_then(|line| line.strip_suffix(']'))
+		{
+			let library = Library {
+				name: name.trim().into(),
+				items: Vec::new(),
+			};
+			current = Some(
+				match libraries.iter().position(|l| l.name == library.name) {
+					Some(index) => {
+						libraries[index] = library;
+						index
+					}
+					None => {
+						libraries.push(library);
+						libraries.len() - 1
+					}
+				},
+			);
+			continue;
+		}
+
+		let Some(index) = current else {
+			errors.push(LibraryError::OutsideLibrary {
+				source: source.into(),
+				line: line_number,
+			});
+			continue;
+		};
+
+		if !line.split('/').all(is_name) {
+			errors.push(LibraryError::InvalidPath {
+				source: source.into(),
+				line: line_number,
+				path: line.into(),
+			});
+			continue;
+		}
+
+		libraries[index].items.push(line.into());
+	}
+}
@@ -1,27 +1,80 @@
-// mod code;
-// mod visual_code;
+mod alias;
+mod animation;
+mod aspect;
+mod binding;
+mod code;
 mod code_widget;
+mod config;
+mod document;
 mod fill_parent_widget;
+mod flow_widget;
+mod gutter_widget;
+mod highlight;
+mod implicit;
+mod keymap;
+mod library;
+mod member;
+mod modal_widget;
+mod mode;
+mod node_widget;
+mod palette;
+mod precedence;
+mod projection;
+mod scope;
+mod status_bar;
+mod theme;
+mod types;
 mod ui;
+mod visual_code;
 
-use anim::{easing::EasingMode, Options, Timeline};
-use fill_parent_widget::FillParent;
+use anim::{easing::EasingMode, Options};
+use animation::{Animations, Purpose};
+use aspect::AspectFilter;
+use code::Code;
+use document::Document;
+use highlight::Highlighter;
 use iced::{
-	color,
+	advanced::widget::operation,
+	keyboard, mouse,
 	widget::{
-		column, container, responsive, row, scrollable, svg, text, vertical_rule, Rule, Space, Svg,
+		column, container, mouse_area, row, scrollable,
+		scrollable::{AbsoluteOffset, Viewport},
+		text,
 	},
-	window, Application, ContentFit, Element, Length, Settings, Subscription,
+	window, Application, Element, Length, Point, Settings, Subscription,
 };
-use std::time::Duration;
+use keymap::{Action, Chord, Keymap};
+use library::Libraries;
+use modal_widget::Modal;
+use mode::Mode;
+use palette::Palette;
+use std::time::{Duration, Instant};
+use theme::Themes;
 
 fn main() -> Result<(), iced::Error> {
 	CodeEditor::run(Settings::default())
 }
 
 struct CodeEditor {
-	// code: code::Code,
-	timeline: Timeline<f32>,
+	documents: Vec<Document>,
+	/// The index of the document shown.
+	active: usize,
+	/// The document waiting for confirmation before it's closed with unsaved
+	/// changes.
+	closing: Option<usize>,
+	/// How many untitled documents were created, to name the next one.
+	untitled: usize,
+	keymap: Keymap,
+	mode: Mode,
+	palette: Palette,
+	cursor: Point,
+	animations: Animations,
+	themes: Themes,
+	highlighter: Highlighter,
+	aspects: AspectFilter,
+	libraries: Libraries,
+	/// What went wrong last, until the next action.
+	error: Option<String>,
 }
 
 impl Application for CodeEditor {
@@ -31,16 +84,53 @@ impl Application for CodeEditor {
 	type Flags = ();
 
 	fn new((): Self::Flags) -> (Self, iced::Command<Message>) {
+		let (keymap, keymap_errors) = Keymap::load();
+		let (themes, theme_errors) = Themes::load();
+		let (libraries, library_errors) = Libraries::load();
+		let errors = keymap_errors
+			.iter()
+			.map(|error| format!("keymap: {error}"))
+			.chain(theme_errors.iter().map(|error| format!("theme: {error}")))
+			.chain(
+				library_errors
+					.iter()
+					.map(|error| format!("libraries: {error}")),
+			);
+
+		let (documents, open_errors): (Vec<_>, Vec<_>) = std::env::args_os()
+			.skip(1)
+			.map(|path| Document::open(path.into()))
+			.partition(Result::is_ok);
+		let mut documents = documents
+			.into_iter()
+			.filter_map(Result::ok)
+			.collect::<Vec<_>>();
+		if documents.is_empty() {
+			documents.push(Document::new("pixels", sample()));
+		}
+
+		let errors = open_errors
+			.into_iter()
+			.filter_map(Result::err)
+			.chain(errors)
+			.collect();
+
 		(
 			Self {
-				// code,
-				timeline: Options::new(1.0, 0.1)
-					.duration(Duration::from_millis(2000))
-					.easing(anim::easing::cubic_ease().mode(EasingMode::InOut))
-					.auto_reverse(true)
-					.delay(Duration::from_millis(400))
-					.forever()
-					.begin_animation(),
+				documents,
+				active: 0,
+				closing: None,
+				untitled: 0,
+				keymap,
+				mode: Mode::Normal,
+				palette: Palette::default(),
+				cursor: Point::ORIGIN,
+				animations: Animations::default(),
+				themes,
+				highlighter: Highlighter::default(),
+				aspects: AspectFilter::default(),
+				libraries,
+				error: summarize(errors),
 			},
 			window::maximize(window::Id::MAIN, true),
 		)
@@ -50,65 +140,956 @@ impl Application for CodeEditor {
 		"Bismuth Editor".into()
 	}
 
+	fn theme(&self) -> iced::Theme {
+		self.themes.current().iced()
+	}
+
 	fn view(&self) -> Element<Message> {
-		container(scrollable(
-			container(row![
-				FillParent,
-				column![
-					text("Hello"),
-					text("Hello"),
-					text("Hello"),
-					text("Hello"),
-					text("Hello"),
-				],
-			])
-			.width(Length::Fill)
-			.padding([24, 36]),
-		))
-		.height(Length::Fill)
-		.center_y()
-		.style(style::screen)
-		.into()
+		let theme = self.themes.current();
+		let document = self.document();
+
+		let tabs = row(self.documents.iter().enumerate().map(|(i, document)| {
+			let name = if document.is_dirty() {
+				format!("{} •", document.name)
+			} else {
+				document.name.clone()
+			};
+
+			let tab = container(text(name).size(14)).padding([4, 10]);
+			let tab = if i == self.active {
+				tab.style(style::line_selected(theme))
+			} else {
+				tab
+			};
+
+			mouse_area(tab).on_press(Message::SwitchDocument(i)).into()
+		}))
+		.spacing(4);
+
+		let editor = container(column![
+			container(tabs).padding([4, 12]),
+			container(
+				scrollable(
+					container(
+						document
+							.visual
+							.view(
+								document,
+								&self.animations,
+								theme,
+								&self.highlighter,
+								&self.aspects,
+								&self.libraries,
+							)
+							.map(Message::Code),
+					)
+					.width(Length::Fill)
+					.padding([24, 36]),
+				)
+				.id(scroll_id())
+				.on_scroll(Message::Scrolled)
+			)
+			.height(Length::Fill)
+			.center_y(),
+			status_bar::view(
+				document,
+				self.mode,
+				&self.aspects,
+				&self.libraries,
+				self.error.as_deref(),
+				theme,
+			)
+			.map(Message::Code),
+		])
+		.style(style::screen(theme));
+
+		// The editor is always wrapped, so showing a dialog over it doesn't
+		// move it in the widget tree and lose where it was scrolled to.
+		let modal = if let Some(closing) = self.closing {
+			let choice = |label, confirm| {
+				mouse_area(
+					container(text(label))
+						.padding([4, 10])
+						.style(style::line_selected(theme)),
+				)
+				.on_press(Message::ConfirmClose(confirm))
+			};
+
+			Some(
+				container(
+					column![
+						text(format!(
+							"{} has unsaved changes. Close it anyway?",
+							self.documents[closing].name
+						)),
+						row![choice("Close (y)", true), choice("Keep (n)", false)].spacing(8),
+					]
+					.spacing(12),
+				)
+				.padding(12)
+				.style(style::palette(theme))
+				.into(),
+			)
+		} else if self.palette.is_open() {
+			Some(
+				self.palette
+					.view(&self.keymap, self.mode, theme)
+					.map(Message::Palette),
+			)
+		} else {
+			None
+		};
+
+		Modal::new(editor, modal).offset(80.0).into()
 	}
 
 	fn update(&mut self, message: Message) -> iced::Command<Message> {
+		match message {
+			Message::Code(message) => {
+				let document = self.document_mut();
+				let selected = document.visual.selected();
+				document.visual.update(&mut document.code, message);
+				self.pulse_on_change(selected);
+			}
+			Message::Palette(message) => {
+				if let Some(action) = self.palette.update(message) {
+					self.palette.close();
+					return self.perform(action);
+				}
+			}
+			Message::SwitchDocument(index) => return self.switch_document(index),
+			Message::ConfirmClose(confirm) => {
+				if let Some(closing) = self.closing.take() {
+					if confirm {
+						return self.close_document(closing);
+					}
+				}
+			}
+			Message::Mouse(_) if self.palette.is_open() || self.closing.is_some() => {}
+			Message::Keyboard(keyboard::Event::KeyPressed { key, .. })
+				if self.closing.is_some() =>
+			{
+				let confirm = match key.as_ref() {
+					keyboard::Key::Character("y")
+					| keyboard::Key::Named(keyboard::key::Named::Enter) => true,
+					keyboard::Key::Character("n")
+					| keyboard::Key::Named(keyboard::key::Named::Escape) => false,
+					_ => return iced::Command::none(),
+				};
+				return self.update(Message::ConfirmClose(confirm));
+			}
+			Message::Keyboard(keyboard::Event::KeyPressed { key, .. })
+				if self.palette.is_open() =>
+			{
+				match key.as_ref() {
+					keyboard::Key::Named(keyboard::key::Named::Escape) => self.palette.close(),
+					keyboard::Key::Named(keyboard::key::Named::ArrowUp) => {
+						self.palette.move_highlight(-1)
+					}
+					keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
+						self.palette.move_highlight(1)
+					}
+					_ => {}
+				}
+			}
+			Message::Mouse(mouse::Event::CursorMoved { position }) => {
+				self.cursor = position;
+				return iced::Command::widget(operation::map(
+					Box::new(node_widget::node_at(position)),
+					|node| Message::Code(visual_code::Message::Hover(node)),
+				));
+			}
+			Message::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+				return iced::Command::widget(operation::map(
+					Box::new(node_widget::node_at(self.cursor)),
+					|node| Message::Code(visual_code::Message::Select(node)),
+				));
+			}
+			Message::Keyboard(keyboard::Event::KeyPressed {
+				key,
+				modifiers,
+				text,
+				..
+			}) => {
+				let Some(chord) = Chord::from_press(&key, modifiers) else {
+					return iced::Command::none();
+				};
+
+				if let Some(action) = self.keymap.press(self.mode, chord) {
+					return self.perform(action);
+				}
+
+				if self.mode == Mode::Insert
+					&& !self.keymap.is_pending()
+					&& !(modifiers.control() || modifiers.alt() || modifiers.logo())
+				{
+					if let Some(text) = text {
+						self.edit_selected_text(|string| string.push_str(&text));
+					}
+				}
+			}
+			Message::Mouse(_) | Message::Keyboard(_) => {}
+			Message::Scrolled(viewport) => self.document_mut().viewport = Some(viewport),
+			Message::Blink => {
+				let (from, to) = if self.document_mut().visual.blink() {
+					(0.0, 1.0)
+				} else {
+					(1.0, 0.0)
+				};
+				self.animations.retarget(
+					Purpose::CursorBlink,
+					from,
+					to,
+					Duration::from_millis(150),
+				);
+			}
+			Message::Tick(now) => self.animations.update(now),
+		}
+
 		iced::Command::none()
 	}
 
 	fn subscription(&self) -> Subscription<Message> {
 		const FPS: f32 = 60.0;
-		Subscription::batch([
-			iced::event::listen_with(|event, _| match event {
-				iced::Event::Keyboard(e) => Some(Message::Keyboard(e)),
-				_ => None,
+		let events = iced::event::listen_with(|event, status| match event {
+			iced::Event::Keyboard(e) => Some(Message::Keyboard(e)),
+			// Clicks a widget like a tab or a breadcrumb already handled don't
+			// select in the code as well.
+			iced::Event::Mouse(mouse::Event::ButtonPressed(_))
+				if status == iced::event::Status::Captured =>
+			{
+				None
+			}
+			iced::Event::Mouse(e) => Some(Message::Mouse(e)),
+			_ => None,
+		});
+
+		let mut subscriptions = vec![events];
+
+		// Redraw continuously only while something moves; animations read the
+		// clock themselves, so a skipped tick doesn't slow them down.
+		if self.animations.is_animating() {
+			subscriptions
+				.push(iced::time::every(Duration::from_secs_f32(1.0 / FPS)).map(Message::Tick));
+		}
+
+		// The cursor only moves for the short fade of each blink, so typing
+		// doesn't keep redrawing at full rate.
+		if self.mode == Mode::Insert {
+			subscriptions
+				.push(iced::time::every(Duration::from_millis(500)).map(|_| Message::Blink));
+		}
+
+		Subscription::batch(subscriptions)
+	}
+}
+
+impl CodeEditor {
+	fn document(&self) -> &Document {
+		&self.documents[self.active]
+	}
+
+	fn document_mut(&mut self) -> &mut Document {
+		&mut self.documents[self.active]
+	}
+
+	fn perform(&mut self, action: Action) -> iced::Command<Message> {
+		if action != Action::OpenPalette {
+			self.palette.record(action);
+		}
+		self.error = None;
+
+		match action {
+			Action::SelectPrevious
+			| Action::SelectNext
+			| Action::SelectParent
+			| Action::SelectFirstChild
+			| Action::SelectRoot
+			| Action::ClearSelection => {
+				let document = &mut self.documents[self.active];
+				let selected = document.visual.selected();
+				document
+					.visual
+					.perform(&document.code, &self.aspects, action);
+				self.pulse_on_change(selected);
+			}
+			Action::NormalMode => self.set_mode(Mode::Normal),
+			Action::InsertMode => {
+				let document = self.document_mut();
+				let is_leaf = document
+					.visual
+					.selected()
+					.and_then(|node_id| document.code.get_mut(node_id))
+					.and_then(|placed| placed.node.text_mut())
+					.is_some();
+
+				if is_leaf {
+					self.set_mode(Mode::Insert);
+				} else {
+					self.error = Some("only text can be typed into".into());
+				}
+			}
+			Action::SelectMode => {
+				if self.document().visual.selected().is_some() {
+					self.set_mode(Mode::Select);
+				} else {
+					self.error = Some("nothing is selected".into());
+				}
+			}
+			Action::DeleteBackward => self.edit_selected_text(|string| {
+				string.pop();
 			}),
-			iced::time::every(Duration::from_secs_f32(1.0 / FPS)).map(|_| Message::Tick),
-		])
+			Action::OpenPalette => {
+				self.keymap.reset();
+				return self.palette.open();
+			}
+			Action::NextTheme => self.themes.next(),
+			Action::ToggleHighlight(category) => self.highlighter.toggle(category),
+			Action::CycleAspect(aspect) => {
+				let document = &mut self.documents[self.active];
+				let selected = document.visual.selected();
+				self.aspects.cycle(aspect);
+				document
+					.visual
+					.reveal_selection(&document.code, &self.aspects);
+				if document.visual.selected() != selected {
+					self.set_mode(Mode::Normal);
+				}
+				self.pulse_on_change(selected);
+			}
+			Action::ReloadTheme => {
+				let errors = self.themes.reload();
+				self.error = summarize(
+					errors
+						.iter()
+						.map(|error| format!("theme: {error}"))
+						.collect(),
+				);
+			}
+			Action::Undo | Action::Redo => {
+				self.set_mode(Mode::Normal);
+				let document = self.document_mut();
+				let selected = document.visual.selected();
+				if action == Action::Undo && !document.undo() {
+					self.error = Some("nothing to undo".into());
+				} else if action == Action::Redo && !document.redo() {
+					self.error = Some("nothing to redo".into());
+				}
+				self.pulse_on_change(selected);
+			}
+			Action::NewDocument => {
+				self.untitled += 1;
+				self.documents.push(Document::new(
+					format!("untitled {}", self.untitled),
+					Code::new(),
+				));
+				return self.switch_document(self.documents.len() - 1);
+			}
+			Action::Save => match self.document_mut().save() {
+				Ok(true) => {}
+				Ok(false) => {
+					let error =
+						"broken references and aliases couldn't be written, so it's unsaved";
+					self.error = Some(error.into());
+				}
+				Err(error) => self.error = Some(error),
+			},
+			Action::CloseDocument => {
+				if self.document().is_dirty() {
+					self.keymap.reset();
+					self.closing = Some(self.active);
+				} else {
+					return self.close_document(self.active);
+				}
+			}
+			Action::DeleteNode => {
+				let document = self.document_mut();
+				let code = &document.code;
+				match document.visual.selected() {
+					Some(selected) if selected != code.root() => {
+						let parent = code.get(selected).and_then(|placed| placed.parent());
+						let siblings = parent.map_or(&[][..], |parent| code.children(parent));
+						let index = siblings.iter().position(|&n| n == selected);
+						let next = index
+							.and_then(|index| {
+								siblings
+									.get(index + 1)
+									.or(index.checked_sub(1).and_then(|i| siblings.get(i)))
+							})
+							.copied()
+							.or(parent);
+
+						let before = binding::dangling(&document.code);
+						document.edit(|code| code.remove(selected));
+						document.visual.forget_removed(&document.code);
+						document
+							.visual
+							.update(&mut document.code, visual_code::Message::Select(next));
+
+						// Only count the references this deletion left behind, not
+						// the ones that were already dangling or got deleted.
+						let dangling = binding::dangling(&document.code)
+							.difference(&before)
+							.count();
+						if dangling > 0 {
+							self.error = Some(format!(
+								"{dangling} reference{} left without a definition",
+								if dangling == 1 { " is" } else { "s are" }
+							));
+						}
+						self.pulse_on_change(Some(selected));
+					}
+					Some(_) => self.error = Some("the root can't be deleted".into()),
+					None => self.error = Some("nothing is selected".into()),
+				}
+			}
+			Action::GoToDefinition => {
+				let document = self.document_mut();
+				let selected = document.visual.selected();
+				let target = selected.and_then(|node_id| match document.code.get(node_id)?.node {
+					code::Node::Reference(target) => Some(Ok(target)),
+					_ => member::resolve(&document.code, node_id)
+						.map(|member| member.map(|member| member.definition)),
+				});
+
+				match target {
+					Some(Ok(target)) if document.code.name(target).is_some() => {
+						document.visual.update(
+							&mut document.code,
+							visual_code::Message::Select(Some(target)),
+						);
+						self.pulse_on_change(selected);
+					}
+					Some(Ok(_)) => self.error = Some("the definition was deleted".into()),
+					Some(Err(error)) => self.error = Some(error.to_string()),
+					None => {
+						self.error = Some("only references and members have a definition".into())
+					}
+				}
+			}
+			Action::CycleScope => {
+				let document = self.document_mut();
+				let group = document.visual.selected().filter(|&node_id| {
+					node_id != document.code.root()
+						&& matches!(
+							document.code.get(node_id).map(|placed| &placed.node),
+							Some(code::Node::Group(_))
+						)
+				});
+
+				match group {
+					Some(group) => document.edit(|code| {
+						if let Some(placed) = code.get_mut(group) {
+							placed.scope = match placed.scope {
+								None => Some(code::Scope::Additive),
+								Some(code::Scope::Additive) => Some(code::Scope::Overwriting),
+								Some(code::Scope::Overwriting) => Some(code::Scope::Isolating),
+								Some(code::Scope::Isolating) => None,
+							};
+						}
+					}),
+					None => self.error = Some("only groups inside the root can be contexts".into()),
+				}
+			}
+			Action::Complete => {
+				let document = self.document();
+				let completion = document.visual.selected().and_then(|node_id| {
+					scope::completions(&document.code, node_id)
+						.first()
+						.map(|&name| name.to_owned())
+						.or_else(|| {
+							self.libraries
+								.completions(&document.code, node_id)
+								.into_iter()
+								.next()
+						})
+				});
+
+				match completion {
+					Some(name) => self.edit_selected_text(|string| *string = name),
+					None => self.error = Some("nothing to complete".into()),
+				}
+			}
+			Action::WrapAmbiguous => {
+				let document = self.document_mut();
+				let selected = document.visual.selected();
+				let code = &document.code;
+				let ambiguity = selected.and_then(|selected| {
+					let parent = code.get(selected).and_then(|placed| placed.parent());
+					precedence::check(code).into_iter().find(|ambiguity| {
+						ambiguity.group == selected || Some(ambiguity.group) == parent
+					})
+				});
+
+				match ambiguity {
+					Some(ambiguity) => {
+						let wrapper =
+							document.edit(|code| code.wrap(ambiguity.group, ambiguity.fix(code)));
+						document
+							.visual
+							.update(&mut document.code, visual_code::Message::Select(wrapper));
+						self.pulse_on_change(selected);
+						self.fade_in(wrapper);
+					}
+					None => self.error = Some("no operators need brackets here".into()),
+				}
+			}
+			Action::ToggleTailDedent => self.document_mut().visual.toggle_tail_dedent(),
+			Action::ToggleImplicitArguments => {
+				self.document_mut().visual.toggle_implicit_arguments()
+			}
+			Action::CopyAlias => {
+				if !self.document_mut().visual.copy() {
+					self.error = Some("nothing is selected".into());
+				}
+			}
+			Action::PasteAlias => {
+				let document = self.document_mut();
+				let selected = document.visual.selected();
+				let (group, index) = after_selection(document);
+
+				let Some(target) = document.visual.copied() else {
+					self.error = Some("nothing was copied".into());
+					return iced::Command::none();
+				};
+				if let Err(error) = alias::check(&document.code, target, group) {
+					self.error = Some(error.to_string());
+					return iced::Command::none();
+				}
+
+				let inserted =
+					document.edit(|code| code.insert_at(code::Node::Alias(target), group, index));
+				document
+					.visual
+					.update(&mut document.code, visual_code::Message::Select(inserted));
+				self.pulse_on_change(selected);
+				self.fade_in(inserted);
+			}
+			Action::ToggleDedent => {
+				let document = &mut self.documents[self.active];
+				if !document.visual.toggle_dedent(&document.code, &self.aspects) {
+					self.error = Some("no block reaches to the end of its group here".into());
+				}
+			}
+			Action::NextDocument => {
+				return self.switch_document((self.active + 1) % self.documents.len());
+			}
+			Action::PreviousDocument => {
+				let count = self.documents.len();
+				return self.switch_document((self.active + count - 1) % count);
+			}
+		}
+
+		iced::Command::none()
+	}
+
+	/// Shows another document, scrolled to where it was left.
+	fn switch_document(&mut self, index: usize) -> iced::Command<Message> {
+		if index >= self.documents.len() {
+			return iced::Command::none();
+		}
+
+		self.set_mode(Mode::Normal);
+		self.active = index;
+
+		let y = self
+			.document()
+			.viewport
+			.map_or(0.0, |viewport| viewport.absolute_offset().y);
+		scrollable::scroll_to(scroll_id(), AbsoluteOffset { x: 0.0, y })
+	}
+
+	/// Closes a document without asking, replacing it with an empty one if
+	/// it was the last.
+	fn close_document(&mut self, index: usize) -> iced::Command<Message> {
+		self.documents.remove(index);
+
+		if self.documents.is_empty() {
+			self.untitled += 1;
+			self.documents.push(Document::new(
+				format!("untitled {}", self.untitled),
+				Code::new(),
+			));
+		}
+
+		let active = if self.active > index {
+			self.active - 1
+		} else {
+			self.active.min(self.documents.len() - 1)
+		};
+		self.switch_document(active)
+	}
+
+	/// Fades in a node that was just put into the code.
+	fn fade_in(&mut self, node_id: Option<code::NodeId>) {
+		if let Some(node_id) = node_id {
+			self.animations.start(
+				Purpose::Insertion(node_id),
+				Options::new(0.0, 1.0).duration(Duration::from_millis(300)),
+			);
+		}
+	}
+
+	/// Draws attention to the selection when it moves, and lights up the
+	/// guide of the block it moves into.
+	fn pulse_on_change(&mut self, previous: Option<code::NodeId>) {
+		let document = self.document();
+		let selected = document.visual.selected();
+		let parent = |node_id| {
+			document
+				.code
+				.get(node_id)
+				.and_then(|placed| placed.parent())
+		};
+		if selected.and_then(parent) != previous.and_then(parent) {
+			self.animations.start(
+				Purpose::Guide,
+				Options::new(0.0, 1.0).duration(Duration::from_millis(300)),
+			);
+		}
+
+		if selected != previous {
+			self.animations.start(
+				Purpose::Selection,
+				Options::new(1.0, 0.1)
+					.duration(Duration::from_millis(2000))
+					.easing(anim::easing::cubic_ease().mode(EasingMode::InOut))
+					.auto_reverse(true)
+					.delay(Duration::from_millis(400))
+					.times(2.0),
+			);
+		}
+	}
+
+	fn set_mode(&mut self, mode: Mode) {
+		let finished_typing = self.mode == Mode::Insert && mode != Mode::Insert;
+		let document = self.document_mut();
+
+		// A literal is recognized once it's typed out, a name is tied to its
+		// definition, and an operator, type, converter, import, member access
+		// or implicit input is recognized.
+		if let Some(node_id) = document.visual.selected().filter(|_| finished_typing) {
+			if let Some(linked) = types::literal(&document.code, node_id)
+				.or_else(|| binding::link(&document.code, node_id))
+				.or_else(|| precedence::operator(&document.code, node_id))
+				.or_else(|| types::node(&document.code, node_id))
+				.or_else(|| library::import(&document.code, node_id))
+				.or_else(|| member::access(&document.code, node_id))
+				.or_else(|| implicit::declare(&document.code, node_id))
+			{
+				document.edit(|code| {
+					if let Some(placed) = code.get_mut(node_id) {
+						placed.node = linked;
+					}
+				});
+			}
+		}
+
+		match mode {
+			Mode::Select => document.visual.start_range(),
+			Mode::Normal | Mode::Insert => document.visual.end_range(),
+		}
+
+		document.visual.set_editing(mode == Mode::Insert);
+		// Everything typed in one go is undone at once.
+		document.group_edits(mode == Mode::Insert);
+		// The cursor starts out shown, and the blink takes over from there.
+		self.animations.cancel(Purpose::CursorBlink);
+
+		self.keymap.reset();
+		self.mode = mode;
+	}
+
+	/// Edits the text of the selected leaf node, if it has any.
+	fn edit_selected_text(&mut self, edit: impl FnOnce(&mut String)) {
+		let document = self.document_mut();
+		let Some(node_id) = document.visual.selected() else {
+			return;
+		};
+
+		let Some(mut string) = document
+			.code
+			.get_mut(node_id)
+			.and_then(|placed| placed.node.text_mut())
+			.cloned()
+		else {
+			return;
+		};
+
+		// Edits that change nothing, like deleting from empty text, don't
+		// make an undo step or mark the document modified.
+		let original = string.clone();
+		edit(&mut string);
+		if string == original {
+			return;
+		}
+
+		document.edit(|code| {
+			if let Some(text) = code
+				.get_mut(node_id)
+				.and_then(|placed| placed.node.text_mut())
+			{
+				*text = string;
+			}
+		});
 	}
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Message {
+	Code(visual_code::Message),
+	Palette(palette::Message),
+	SwitchDocument(usize),
+	/// Whether to close the document with unsaved changes after all.
+	ConfirmClose(bool),
 	Keyboard(iced::keyboard::Event),
-	Tick,
+	Mouse(mouse::Event),
+	Scrolled(Viewport),
+	Tick(Instant),
+	/// Time for the cursor to fade in or out.
+	Blink,
+}
+
+/// The code shown when no files are opened, with a bit of everything the
+/// editor can show.
+fn sample() -> Code {
+	let mut code = Code::new();
+	code.insert(code::Node::Text("pixels".into()), code.root());
+	for row in 0..2 {
+		let group = code
+			.insert(code::Node::Group(Vec::new()), code.root())
+			.unwrap();
+		for column in 0..2 {
+			code.insert(
+				code::Node::Pixel {
+					position: (row, column),
+					color: code::Color::Red,
+				},
+				group,
+			);
+		}
+	}
+	let docs = code
+		.insert(
+			code::Node::Text("a grid of pixels, row by row".into()),
+			code.root(),
+		)
+		.unwrap();
+	code.get_mut(docs)
+		.unwrap()
+		.aspects
+		.insert(code::Aspect::Docs);
+	let line = |code: &mut Code, nodes: Vec<code::Node>| {
+		let line = code
+			.insert(code::Node::Group(Vec::new()), code.root())
+			.unwrap();
+		nodes
+			.into_iter()
+			.filter_map(|node| code.insert(node, line))
+			.collect::<Vec<_>>()
+	};
+	let text = |string: &str| code::Node::Text(string.into());
+	let literal = |string: &str| code::Node::Literal(string.into());
+	let operator = |string: &str| code::Node::Operator(string.into());
+	let size = line(
+		&mut code,
+		vec![
+			code::Node::Definition("size".into()),
+			text("="),
+			literal("2"),
+		],
+	)[0];
+	line(
+		&mut code,
+		vec![code::Node::Reference(size), text(":="), literal("4")],
+	);
+	line(
+		&mut code,
+		vec![
+			code::Node::Converter {
+				from: "Rng".into(),
+				to: "Int".into(),
+			},
+			text(":"),
+			text("next"),
+		],
+	);
+
+	// A function taking the console from wherever it's called.
+	let greet = line(
+		&mut code,
+		vec![code::Node::Definition("greet".into()), text("=")],
+	);
+	let binding = code
+		.get(greet[0])
+		.and_then(|placed| placed.parent())
+		.unwrap();
+	code.insert(code::Node::Implicit("Console".into()), binding);
+	let body = code.insert(code::Node::Group(Vec::new()), binding).unwrap();
+	for node in [
+		code::Node::Type("Console".into()),
+		text("print"),
+		literal("\"hello\""),
+	] {
+		code.insert(node, body);
+	}
+	code.insert(code::Node::Reference(greet[0]), code.root());
+
+	// The greeting shown again rather than repeated, so editing either
+	// edits both.
+	let greeting = code.children(body)[2];
+	line(
+		&mut code,
+		vec![
+			code::Node::Type("Console".into()),
+			text("print"),
+			code::Node::Alias(greeting),
+		],
+	);
+
+	// A value with a stored member and one worked out from it.
+	let record = line(
+		&mut code,
+		vec![code::Node::Definition("array".into()), text("=")],
+	);
+	let binding = code
+		.get(record[0])
+		.and_then(|placed| placed.parent())
+		.unwrap();
+	let field = |code: &mut Code, nodes: Vec<code::Node>| {
+		let field = code.insert(code::Node::Group(Vec::new()), binding).unwrap();
+		for node in nodes {
+			code.insert(node, field);
+		}
+		field
+	};
+	let length = field(
+		&mut code,
+		vec![
+			code::Node::Definition("length".into()),
+			text("="),
+			literal("3"),
+		],
+	);
+	let length = code.children(length)[0];
+	let last = field(
+		&mut code,
+		vec![code::Node::Definition("last".into()), text("=")],
+	);
+	let block = code.insert(code::Node::Group(Vec::new()), last).unwrap();
+	for node in [code::Node::Reference(length), operator("-"), literal("1")] {
+		code.insert(node, block);
+	}
+	line(
+		&mut code,
+		vec![
+			text("print"),
+			code::Node::Member {
+				object: record[0],
+				member: "length".into(),
+			},
+			code::Node::Member {
+				object: record[0],
+				member: "last".into(),
+			},
+		],
+	);
+
+	line(
+		&mut code,
+		vec![
+			code::Node::Type("Console".into()),
+			text("print"),
+			code::Node::Reference(size),
+			code::Node::Type("Int".into()),
+		],
+	);
+	line(
+		&mut code,
+		vec![
+			literal("1"),
+			operator("+"),
+			code::Node::Reference(size),
+			operator("*"),
+			literal("3"),
+			operator("<"),
+			literal("10"),
+			operator("&&"),
+			literal("true"),
+			operator("||"),
+			literal("false"),
+		],
+	);
+
+	code
+}
+
+/// Prints every error and returns the first one to show, mentioning how many
+/// more there are.
+fn summarize(errors: Vec<String>) -> Option<String> {
+	for error in &errors {
+		eprintln!("{error}");
+	}
+
+	let first = errors.first()?;
+	Some(match errors.len() {
+		1 => first.clone(),
+		count => format!("{first} (and {} more)", count - 1),
+	})
+}
+
+/// Where a node inserted after the selection goes: the group and index,
+/// or the end of the document if nothing is selected.
+fn after_selection(document: &Document) -> (code::NodeId, usize) {
+	let code = &document.code;
+	document
+		.visual
+		.selected()
+		.and_then(|selected| {
+			let parent = code.get(selected)?.parent()?;
+			let index = code.children(parent).iter().position(|&n| n == selected)?;
+			Some((parent, index + 1))
+		})
+		.unwrap_or((code.root(), usize::MAX))
+}
+
+fn scroll_id() -> scrollable::Id {
+	scrollable::Id::new("code")
 }
 
 mod style {
+	use crate::theme::Theme;
 	use iced::border::Radius;
-	use iced::{color, Border};
-	use iced::{widget::container, Color, Theme};
+	use iced::widget::container;
+	use iced::{Border, Color};
+
+	pub fn screen(theme: &Theme) -> impl Fn(&iced::Theme) -> container::Appearance {
+		let (background, text) = (theme.background, theme.text);
+		move |_| container::Appearance {
+			background: Some(background.into()),
+			text_color: Some(text),
+			..Default::default()
+		}
+	}
 
-	pub fn screen(_: &Theme) -> container::Appearance {
-		container::Appearance {
-			background: Some(color!(0x322442).into()),
-			text_color: Some(Color::WHITE),
+	pub fn palette(theme: &Theme) -> impl Fn(&iced::Theme) -> container::Appearance {
+		let (background, text, border) = (theme.line_selection, theme.text, theme.guide_active);
+		move |_| container::Appearance {
+			background: Some(background.into()),
+			text_color: Some(text),
+			border: Border {
+				color: border,
+				width: 1.0,
+				radius: Radius::from(6),
+			},
 			..Default::default()
 		}
 	}
 
-	pub fn line_selected(_: &Theme) -> container::Appearance {
-		container::Appearance {
-			background: Some(color!(0x281D34).into()),
+	pub fn line_selected(theme: &Theme) -> impl Fn(&iced::Theme) -> container::Appearance {
+		let background = theme.line_selection;
+		move |_| container::Appearance {
+			background: Some(background.into()),
 			border: Border {
 				color: Color::TRANSPARENT,
 				width: 0.0,
@@ -118,9 +1099,29 @@ mod style {
 		}
 	}
 
-	pub fn word_selected(opacity: f32) -> impl Fn(&Theme) -> container::Appearance {
+	/// Tells aliases apart from the node they show.
+	pub fn aliased(theme: &Theme) -> impl Fn(&iced::Theme) -> container::Appearance {
+		let border = theme.guide_active;
+		move |_| container::Appearance {
+			border: Border {
+				color: border,
+				width: 1.0,
+				radius: Radius::from(4),
+			},
+			..Default::default()
+		}
+	}
+
+	pub fn word_selected(
+		theme: &Theme,
+		opacity: f32,
+	) -> impl Fn(&iced::Theme) -> container::Appearance {
+		let background = Color {
+			a: theme.word_selection.a * opacity,
+			..theme.word_selection
+		};
 		move |_| container::Appearance {
-			background: Some(color!(0x6E5091, opacity).into()),
+			background: Some(background.into()),
 			border: Border {
 				color: Color::TRANSPARENT,
 				width: 0.0,
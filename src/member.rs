@@ -0,0 +1,151 @@
+//! Access the members of a value the same way whether they're stored or
+//! computed, since the place using them doesn't care.
+use crate::binding::is_name;
+use crate::code::{Code, Node, NodeId};
+use crate::scope;
+use std::collections::HashSet;
+use std::fmt;
+
+/// How a member gets its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+	/// Kept with the value, like `length = 3`.
+	Stored,
+	/// Worked out from the value without any arguments, like a `length =`
+	/// binding followed by a block.
+	Computed,
+}
+
+impl Kind {
+	pub fn name(self) -> &'static str {
+		match self {
+			Kind::Stored => "stored field",
+			Kind::Computed => "computed without arguments",
+		}
+	}
+}
+
+/// The definition a member access refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Member {
+	pub definition: NodeId,
+	pub kind: Kind,
+}
+
+/// Why a member access doesn't refer to a member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemberError {
+	/// The definition of the value was deleted.
+	Deleted,
+	Unknown {
+		object: String,
+		member: String,
+	},
+}
+
+impl fmt::Display for MemberError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			MemberError::Deleted => write!(f, "the definition was deleted"),
+			MemberError::Unknown { object, member } => {
+				write!(f, "`{object}` has no member `{member}`")
+			}
+		}
+	}
+}
+
+/// The node a text node should become once it's typed: a member access if
+/// it's written like `object.member`, or `object.member()`, and the object
+/// is available at it.
+pub fn access(code: &Code, node_id: NodeId) -> Option<Node> {
+	let Node::Text(string) = &code.get(node_id)?.node else {
+		return None;
+	};
+
+	let string = string.trim();
+	let (object, member) = string
+		.strip_suffix("()")
+		.unwrap_or(string)
+		.split_once('.')?;
+	if !is_name(member) {
+		return None;
+	}
+
+	let object = *scope::visible(code, node_id).get(object)?;
+	Some(Node::Member {
+		object,
+		member: member.into(),
+	})
+}
+
+/// Finds the member a member access node refers to.
+pub fn resolve(code: &Code, node_id: NodeId) -> Option<Result<Member, MemberError>> {
+	let Node::Member { object, member } = &code.get(node_id)?.node else {
+		return None;
+	};
+	let Some(name) = code.name(*object) else {
+		return Some(Err(MemberError::Deleted));
+	};
+
+	let found = members(code, *object)
+		.into_iter()
+		.find(|&definition| code.name(definition) == Some(member));
+	Some(found.map_or_else(
+		|| {
+			Err(MemberError::Unknown {
+				object: name.into(),
+				member: member.clone(),
+			})
+		},
+		|definition| {
+			Ok(Member {
+				definition,
+				kind: kind(code, definition),
+			})
+		},
+	))
+}
+
+/// The definitions made by the lines indented under a definition's binding,
+/// like the fields of a record.
+fn members(code: &Code, definition: NodeId) -> Vec<NodeId> {
+	let Some(binding) = code.get(definition).and_then(|placed| placed.parent()) else {
+		return Vec::new();
+	};
+
+	code.children(binding)
+		.iter()
+		.filter_map(|&line| code.children(line).first().copied())
+		.filter(|&first| {
+			matches!(
+				code.get(first).map(|placed| &placed.node),
+				Some(Node::Definition(_))
+			)
+		})
+		.collect()
+}
+
+/// Whether a member is bound to a block working out its value, rather than
+/// to the value itself.
+fn kind(code: &Code, definition: NodeId) -> Kind {
+	let binding = code.get(definition).and_then(|placed| placed.parent());
+	let has_block = binding.is_some_and(|binding| {
+		code.children(binding)
+			.iter()
+			.any(|&child| !code.children(child).is_empty())
+	});
+
+	if has_block {
+		Kind::Computed
+	} else {
+		Kind::Stored
+	}
+}
+
+/// The member access nodes that don't refer to a member.
+pub fn unresolved(code: &Code) -> HashSet<NodeId> {
+	code.walk(code.root())
+		.into_iter()
+		.filter(|&node_id| resolve(code, node_id).is_some_and(|member| member.is_err()))
+		.collect()
+}
@@ -0,0 +1,300 @@
+//! Show content on top of the rest of the interface.
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::overlay;
+use iced::advanced::renderer;
+use iced::advanced::widget::{Operation, Tree};
+use iced::advanced::{Clipboard, Shell, Widget};
+use iced::event::{self, Event};
+use iced::mouse;
+use iced::{Alignment, Element, Length, Point, Rectangle, Size, Vector};
+
+/// A widget showing its modal content, if it has any, centered horizontally
+/// on top of its base content.
+///
+/// The base content stays in the same place in the widget tree whether the
+/// modal content is shown or not, so it keeps its state, like how far it's
+/// scrolled.
+#[allow(missing_debug_implementations)]
+pub struct Modal<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer> {
+	base: Element<'a, Message, Theme, Renderer>,
+	modal: Option<Element<'a, Message, Theme, Renderer>>,
+	offset: f32,
+}
+
+impl<'a, Message, Theme, Renderer> Modal<'a, Message, Theme, Renderer> {
+	/// Creates a [`Modal`] showing `modal`, if any, on top of `base`.
+	pub fn new(
+		base: impl Into<Element<'a, Message, Theme, Renderer>>,
+		modal: Option<Element<'a, Message, Theme, Renderer>>,
+	) -> Self {
+		Self {
+			base: base.into(),
+			modal,
+			offset: 0.0,
+		}
+	}
+
+	/// Sets the distance between the top of the base content and the modal
+	/// content.
+	pub fn offset(mut self, offset: f32) -> Self {
+		self.offset = offset;
+		self
+	}
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+	for Modal<'a, Message, Theme, Renderer>
+where
+	Renderer: iced::advanced::Renderer,
+{
+	fn children(&self) -> Vec<Tree> {
+		std::iter::once(&self.base)
+			.chain(&self.modal)
+			.map(Tree::new)
+			.collect()
+	}
+
+	fn diff(&self, tree: &mut Tree) {
+		let children = std::iter::once(&self.base)
+			.chain(&self.modal)
+			.collect::<Vec<_>>();
+		tree.diff_children(&children);
+	}
+
+	fn size(&self) -> Size<Length> {
+		self.base.as_widget().size()
+	}
+
+	fn layout(
+		&self,
+		tree: &mut Tree,
+		renderer: &Renderer,
+		limits: &layout::Limits,
+	) -> layout::Node {
+		self.base
+			.as_widget()
+			.layout(&mut tree.children[0], renderer, limits)
+	}
+
+	fn operate(
+		&self,
+		tree: &mut Tree,
+		layout: Layout<'_>,
+		renderer: &Renderer,
+		operation: &mut dyn Operation<Message>,
+	) {
+		self.base
+			.as_widget()
+			.operate(&mut tree.children[0], layout, renderer, operation);
+	}
+
+	fn on_event(
+		&mut self,
+		tree: &mut Tree,
+		event: Event,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		renderer: &Renderer,
+		clipboard: &mut dyn Clipboard,
+		shell: &mut Shell<'_, Message>,
+		viewport: &Rectangle,
+	) -> event::Status {
+		self.base.as_widget_mut().on_event(
+			&mut tree.children[0],
+			event,
+			layout,
+			cursor,
+			renderer,
+			clipboard,
+			shell,
+			viewport,
+		)
+	}
+
+	fn mouse_interaction(
+		&self,
+		tree: &Tree,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		viewport: &Rectangle,
+		renderer: &Renderer,
+	) -> mouse::Interaction {
+		self.base.as_widget().mouse_interaction(
+			&tree.children[0],
+			layout,
+			cursor,
+			viewport,
+			renderer,
+		)
+	}
+
+	fn draw(
+		&self,
+		tree: &Tree,
+		renderer: &mut Renderer,
+		theme: &Theme,
+		style: &renderer::Style,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		viewport: &Rectangle,
+	) {
+		self.base.as_widget().draw(
+			&tree.children[0],
+			renderer,
+			theme,
+			style,
+			layout,
+			cursor,
+			viewport,
+		);
+	}
+
+	fn overlay<'b>(
+		&'b mut self,
+		tree: &'b mut Tree,
+		layout: Layout<'_>,
+		_renderer: &Renderer,
+		translation: Vector,
+	) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+		let content = self.modal.as_mut()?;
+
+		Some(overlay::Element::new(Box::new(Overlay {
+			position: layout.position() + translation,
+			size: layout.bounds().size(),
+			offset: self.offset,
+			content,
+			tree: &mut tree.children[1],
+		})))
+	}
+}
+
+struct Overlay<'a, 'b, Message, Theme, Renderer> {
+	position: Point,
+	size: Size,
+	offset: f32,
+	content: &'b mut Element<'a, Message, Theme, Renderer>,
+	tree: &'b mut Tree,
+}
+
+impl<'a, 'b, Message, Theme, Renderer> overlay::Overlay<Message, Theme, Renderer>
+	for Overlay<'a, 'b, Message, Theme, Renderer>
+where
+	Renderer: iced::advanced::Renderer,
+{
+	fn layout(&mut self, renderer: &Renderer, _bounds: Size) -> layout::Node {
+		let limits = layout::Limits::new(
+			Size::ZERO,
+			Size::new(self.size.width, self.size.height - self.offset),
+		);
+
+		let content = self
+			.content
+			.as_widget()
+			.layout(self.tree, renderer, &limits)
+			.align(Alignment::Center, Alignment::Start, limits.max())
+			.translate(Vector::new(0.0, self.offset));
+
+		layout::Node::with_children(self.size, vec![content]).move_to(self.position)
+	}
+
+	fn on_event(
+		&mut self,
+		event: Event,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		renderer: &Renderer,
+		clipboard: &mut dyn Clipboard,
+		shell: &mut Shell<'_, Message>,
+	) -> event::Status {
+		let status = self.content.as_widget_mut().on_event(
+			self.tree,
+			event.clone(),
+			layout.children().next().unwrap(),
+			cursor,
+			renderer,
+			clipboard,
+			shell,
+			&layout.bounds(),
+		);
+
+		// Nothing underneath the modal content can be interacted with.
+		match event {
+			Event::Mouse(_) => event::Status::Captured,
+			_ => status,
+		}
+	}
+
+	fn draw(
+		&self,
+		renderer: &mut Renderer,
+		theme: &Theme,
+		style: &renderer::Style,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+	) {
+		self.content.as_widget().draw(
+			self.tree,
+			renderer,
+			theme,
+			style,
+			layout.children().next().unwrap(),
+			cursor,
+			&layout.bounds(),
+		);
+	}
+
+	fn operate(
+		&mut self,
+		layout: Layout<'_>,
+		renderer: &Renderer,
+		operation: &mut dyn Operation<Message>,
+	) {
+		self.content.as_widget().operate(
+			self.tree,
+			layout.children().next().unwrap(),
+			renderer,
+			operation,
+		);
+	}
+
+	fn mouse_interaction(
+		&self,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		viewport: &Rectangle,
+		renderer: &Renderer,
+	) -> mouse::Interaction {
+		self.content.as_widget().mouse_interaction(
+			self.tree,
+			layout.children().next().unwrap(),
+			cursor,
+			viewport,
+			renderer,
+		)
+	}
+
+	fn overlay<'c>(
+		&'c mut self,
+		layout: Layout<'_>,
+		renderer: &Renderer,
+	) -> Option<overlay::Element<'c, Message, Theme, Renderer>> {
+		self.content.as_widget_mut().overlay(
+			self.tree,
+			layout.children().next().unwrap(),
+			renderer,
+			Vector::ZERO,
+		)
+	}
+}
+
+impl<'a, Message, Theme, Renderer> From<Modal<'a, Message, Theme, Renderer>>
+	for Element<'a, Message, Theme, Renderer>
+where
+	Message: 'a,
+	Theme: 'a,
+	Renderer: iced::advanced::Renderer + 'a,
+{
+	fn from(modal: Modal<'a, Message, Theme, Renderer>) -> Self {
+		Self::new(modal)
+	}
+}
@@ -0,0 +1,30 @@
+/// What keys do in the editor at the moment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Mode {
+	/// Keys navigate the tree and run structural commands.
+	#[default]
+	Normal,
+
+	/// Keys type into the selected leaf node.
+	Insert,
+
+	/// Keys extend the selected range of nodes.
+	Select,
+}
+
+impl Mode {
+	pub const ALL: &'static [Mode] = &[Mode::Normal, Mode::Insert, Mode::Select];
+
+	/// The name the mode is referred to by in keymap files.
+	pub fn name(self) -> &'static str {
+		match self {
+			Mode::Normal => "normal",
+			Mode::Insert => "insert",
+			Mode::Select => "select",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Mode> {
+		Mode::ALL.iter().copied().find(|mode| mode.name() == name)
+	}
+}
@@ -0,0 +1,226 @@
+//! Tie the widgets displaying code back to the nodes they display.
+use crate::code::NodeId;
+use iced::advanced::layout;
+use iced::advanced::overlay;
+use iced::advanced::renderer;
+use iced::advanced::widget::operation::Outcome;
+use iced::advanced::widget::{Id, Operation, Tree};
+use iced::event::{self, Event};
+use iced::mouse;
+use iced::{
+	advanced::Clipboard, advanced::Layout, advanced::Shell, advanced::Widget, Element, Length,
+	Point, Rectangle, Size, Vector,
+};
+use std::any::Any;
+
+/// A widget displaying the code node with the given [`NodeId`].
+#[allow(missing_debug_implementations)]
+pub struct Tagged<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer> {
+	node: NodeId,
+	content: Element<'a, Message, Theme, Renderer>,
+}
+
+/// What a [`Tagged`] widget exposes to an [`Operation`].
+#[derive(Debug, Clone, Copy)]
+pub struct Target {
+	pub node: NodeId,
+	pub bounds: Rectangle,
+}
+
+impl<'a, Message, Theme, Renderer> Tagged<'a, Message, Theme, Renderer> {
+	/// Creates a [`Tagged`] widget displaying `node` with the given content.
+	pub fn new(node: NodeId, content: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+		Self {
+			node,
+			content: content.into(),
+		}
+	}
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+	for Tagged<'a, Message, Theme, Renderer>
+where
+	Renderer: iced::advanced::Renderer,
+{
+	fn children(&self) -> Vec<Tree> {
+		vec![Tree::new(&self.content)]
+	}
+
+	fn diff(&self, tree: &mut Tree) {
+		tree.diff_children(std::slice::from_ref(&self.content));
+	}
+
+	fn size(&self) -> Size<Length> {
+		self.content.as_widget().size()
+	}
+
+	fn layout(
+		&self,
+		tree: &mut Tree,
+		renderer: &Renderer,
+		limits: &layout::Limits,
+	) -> layout::Node {
+		self.content
+			.as_widget()
+			.layout(&mut tree.children[0], renderer, limits)
+	}
+
+	fn operate(
+		&self,
+		tree: &mut Tree,
+		layout: Layout<'_>,
+		renderer: &Renderer,
+		operation: &mut dyn Operation<Message>,
+	) {
+		operation.custom(
+			&mut Target {
+				node: self.node,
+				bounds: layout.bounds(),
+			},
+			None,
+		);
+
+		self.content
+			.as_widget()
+			.operate(&mut tree.children[0], layout, renderer, operation);
+	}
+
+	fn on_event(
+		&mut self,
+		tree: &mut Tree,
+		event: Event,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		renderer: &Renderer,
+		clipboard: &mut dyn Clipboard,
+		shell: &mut Shell<'_, Message>,
+		viewport: &Rectangle,
+	) -> event::Status {
+		self.content.as_widget_mut().on_event(
+			&mut tree.children[0],
+			event,
+			layout,
+			cursor,
+			renderer,
+			clipboard,
+			shell,
+			viewport,
+		)
+	}
+
+	fn mouse_interaction(
+		&self,
+		tree: &Tree,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		viewport: &Rectangle,
+		renderer: &Renderer,
+	) -> mouse::Interaction {
+		self.content.as_widget().mouse_interaction(
+			&tree.children[0],
+			layout,
+			cursor,
+			viewport,
+			renderer,
+		)
+	}
+
+	fn draw(
+		&self,
+		tree: &Tree,
+		renderer: &mut Renderer,
+		theme: &Theme,
+		style: &renderer::Style,
+		layout: Layout<'_>,
+		cursor: mouse::Cursor,
+		viewport: &Rectangle,
+	) {
+		self.content.as_widget().draw(
+			&tree.children[0],
+			renderer,
+			theme,
+			style,
+			layout,
+			cursor,
+			viewport,
+		);
+	}
+
+	fn overlay<'b>(
+		&'b mut self,
+		tree: &'b mut Tree,
+		layout: Layout<'_>,
+		renderer: &Renderer,
+		translation: Vector,
+	) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+		self.content
+			.as_widget_mut()
+			.overlay(&mut tree.children[0], layout, renderer, translation)
+	}
+}
+
+impl<'a, Message, Theme, Renderer> From<Tagged<'a, Message, Theme, Renderer>>
+	for Element<'a, Message, Theme, Renderer>
+where
+	Message: 'a,
+	Theme: 'a,
+	Renderer: iced::advanced::Renderer + 'a,
+{
+	fn from(tagged: Tagged<'a, Message, Theme, Renderer>) -> Self {
+		Self::new(tagged)
+	}
+}
+
+/// Produces the deepest [`NodeId`] whose widget contains the given window
+/// position, taking scrolled content into account.
+pub fn node_at(position: Point) -> impl Operation<Option<NodeId>> {
+	struct NodeAt {
+		position: Point,
+		translation: Vector,
+		pending: Vector,
+		found: Option<NodeId>,
+	}
+
+	impl Operation<Option<NodeId>> for NodeAt {
+		fn container(
+			&mut self,
+			_id: Option<&Id>,
+			_bounds: Rectangle,
+			operate_on_children: &mut dyn FnMut(&mut dyn Operation<Option<NodeId>>),
+		) {
+			let translation = self.translation;
+			self.translation = translation + std::mem::replace(&mut self.pending, Vector::ZERO);
+			operate_on_children(self);
+			self.translation = translation;
+		}
+
+		fn scrollable(
+			&mut self,
+			_state: &mut dyn iced::advanced::widget::operation::Scrollable,
+			_id: Option<&Id>,
+			_bounds: Rectangle,
+			translation: Vector,
+		) {
+			self.pending = translation;
+		}
+
+		fn custom(&mut self, state: &mut dyn Any, _id: Option<&Id>) {
+			if let Some(target) = state.downcast_ref::<Target>() {
+				if target.bounds.contains(self.position + self.translation) {
+					self.found = Some(target.node);
+				}
+			}
+		}
+
+		fn finish(&self) -> Outcome<Option<NodeId>> {
+			Outcome::Some(self.found)
+		}
+	}
+
+	NodeAt {
+		position,
+		translation: Vector::ZERO,
+		pending: Vector::ZERO,
+		found: None,
+	}
+}
@@ -0,0 +1,158 @@
+//! Find and perform any editor action by name.
+use crate::keymap::{Action, Keymap};
+use crate::mode::Mode;
+use crate::style;
+use crate::theme::Theme;
+use iced::widget::{column, container, mouse_area, row, scrollable, text, text_input};
+use iced::{Element, Length};
+use std::cmp::Reverse;
+
+#[derive(Debug, Default)]
+pub struct Palette {
+	open: bool,
+	query: String,
+	highlighted: usize,
+	/// Performed actions, the most recent first.
+	recent: Vec<Action>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+	Query(String),
+	Submit,
+	Pick(Action),
+}
+
+impl Palette {
+	pub fn is_open(&self) -> bool {
+		self.open
+	}
+
+	pub fn open<Message: 'static>(&mut self) -> iced::Command<Message> {
+		self.open = true;
+		self.query.clear();
+		self.highlighted = 0;
+		text_input::focus(input_id())
+	}
+
+	pub fn close(&mut self) {
+		self.open = false;
+	}
+
+	/// Remembers that the action was performed, so it's listed first.
+	pub fn record(&mut self, action: Action) {
+		self.recent.retain(|&recent| recent != action);
+		self.recent.insert(0, action);
+	}
+
+	pub fn move_highlight(&mut self, offset: isize) {
+		let last = self.entries().len().saturating_sub(1);
+		self.highlighted = self.highlighted.saturating_add_signed(offset).min(last);
+	}
+
+	/// Returns the action to perform, if one was chosen.
+	pub fn update(&mut self, message: Message) -> Option<Action> {
+		match message {
+			Message::Query(query) => {
+				self.query = query;
+				self.highlighted = 0;
+				None
+			}
+			Message::Submit => self.entries().get(self.highlighted).copied(),
+			Message::Pick(action) => Some(action),
+		}
+	}
+
+	/// The actions matching the query, best matches first and recently
+	/// performed ones first among equal matches.
+	pub fn entries(&self) -> Vec<Action> {
+		let recency = |action| {
+			self.recent
+				.iter()
+				.position(|&recent| recent == action)
+				.unwrap_or(usize::MAX)
+		};
+
+		let mut entries = Action::ALL
+			.iter()
+			.filter_map(|&action| Some((action, fuzzy_score(&self.query, &label(action))?)))
+			.collect::<Vec<_>>();
+		entries.sort_by_key(|&(action, score)| (Reverse(score), recency(action)));
+		entries.into_iter().map(|(action, _)| action).collect()
+	}
+
+	/// Lists the entries with the keys bound to them in the given mode.
+	pub fn view<'a>(&'a self, keymap: &Keymap, mode: Mode, theme: &Theme) -> Element<'a, Message> {
+		let entries = self.entries().into_iter().enumerate().map(|(i, action)| {
+			let entry = container(
+				row![
+					text(label(action)).width(Length::Fill),
+					text(keymap.sequences(mode, action).join(", ")).size(12),
+				]
+				.spacing(12),
+			)
+			.width(Length::Fill)
+			.padding([4, 8]);
+
+			let entry = if i == self.highlighted {
+				entry.style(style::line_selected(theme))
+			} else {
+				entry
+			};
+
+			mouse_area(entry).on_press(Message::Pick(action)).into()
+		});
+
+		container(
+			column![
+				text_input("Type an action", &self.query)
+					.id(input_id())
+					.on_input(Message::Query)
+					.on_submit(Message::Submit)
+					.padding(8),
+				scrollable(column(entries)).height(Length::Shrink),
+			]
+			.spacing(8),
+		)
+		.width(480)
+		.max_height(360)
+		.padding(8)
+		.style(style::palette(theme))
+		.into()
+	}
+}
+
+fn input_id() -> text_input::Id {
+	text_input::Id::new("palette")
+}
+
+/// How an action is shown in the palette.
+fn label(action: Action) -> String {
+	action.name().replace('_', " ")
+}
+
+/// Scores how well the query matches the candidate, if the query's letters
+/// appear in it in order.
+///
+/// Letters that follow each other or start a word score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+	let candidate = candidate.to_lowercase().chars().collect::<Vec<_>>();
+	let mut score = 0;
+	let mut next = 0;
+
+	for letter in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+		let index = (next..candidate.len()).find(|&i| candidate[i] == letter)?;
+
+		score += 1;
+		if index > 0 && index == next {
+			score += 2;
+		}
+		if index == 0 || candidate[index - 1] == ' ' {
+			score += 3;
+		}
+
+		next = index + 1;
+	}
+
+	Some(score)
+}
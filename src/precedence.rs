@@ -0,0 +1,204 @@
+//! Decide which operators can be mixed without brackets.
+//!
+//! Precedence is a partial order: two operators without a defined
+//! relationship can't be used in the same group, so the reader never has to
+//! remember how they combine.
+use crate::code::{Code, Node, NodeId};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Operators that bind equally tightly, from tightest to loosest.
+const LEVELS: &[&[&str]] = &[
+	&["*", "/", "%"],
+	&["+", "-"],
+	&["==", "!=", "<", ">", "<=", ">="],
+	&["&&"],
+	&["||"],
+];
+
+/// Pairs of levels where the first binds tighter than the second. The levels
+/// that can't be reached from each other through these aren't related, like
+/// `&&` and `||`.
+const TIGHTER: &[(usize, usize)] = &[(0, 1), (1, 2), (2, 3), (2, 4)];
+
+/// The level of an operator, if it's one.
+fn level(operator: &str) -> Option<usize> {
+	LEVELS.iter().position(|level| level.contains(&operator))
+}
+
+/// Whether level `a` binds tighter than level `b`, directly or through other
+/// levels.
+fn tighter(a: usize, b: usize) -> bool {
+	TIGHTER
+		.iter()
+		.any(|&(tight, loose)| tight == a && (loose == b || tighter(loose, b)))
+}
+
+/// How tightly `a` binds compared to `b`, or `None` if they can't be mixed.
+pub fn compare(a: &str, b: &str) -> Option<Ordering> {
+	let (a, b) = (level(a)?, level(b)?);
+
+	if a == b {
+		Some(Ordering::Equal)
+	} else if tighter(a, b) {
+		Some(Ordering::Greater)
+	} else if tighter(b, a) {
+		Some(Ordering::Less)
+	} else {
+		None
+	}
+}
+
+/// The operator node a text node should become once it's typed.
+pub fn operator(code: &Code, node_id: NodeId) -> Option<Node> {
+	match &code.get(node_id)?.node {
+		Node::Text(text) if level(text.trim()).is_some() => {
+			Some(Node::Operator(text.trim().into()))
+		}
+		_ => None,
+	}
+}
+
+/// Two operators in the same group without a defined precedence
+/// relationship.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ambiguity {
+	pub group: NodeId,
+	/// The indices of the operators among the group's children, the first
+	/// one first.
+	pub operators: (usize, usize),
+}
+
+impl Ambiguity {
+	/// The children to wrap in a group of their own to remove the ambiguity:
+	/// everything up to the second operator that the first one applies to,
+	/// including operands of operators binding tighter than it.
+	pub fn fix(&self, code: &Code) -> Range<usize> {
+		let (first, second) = self.operators;
+		let children = code.children(self.group);
+		let operator = |i: usize| match code.get(*children.get(i)?).map(|placed| &placed.node) {
+			Some(Node::Operator(operator)) => Some(operator.as_str()),
+			_ => None,
+		};
+
+		let mut start = first.saturating_sub(1);
+		while let Some(previous) = (0..start).rev().find(|&i| operator(i).is_some()) {
+			match (operator(previous), operator(first)) {
+				(Some(a), Some(b)) if compare(a, b) == Some(Ordering::Greater) => {
+					start = previous.saturating_sub(1);
+				}
+				_ => break,
+			}
+		}
+
+		start..second
+	}
+}
+
+/// The groups mixing operators that can't be mixed.
+pub fn check(code: &Code) -> Vec<Ambiguity> {
+	let mut ambiguities = Vec::new();
+
+	for group in code.walk(code.root()) {
+		let operators = code
+			.children(group)
+			.iter()
+			.enumerate()
+			.filter_map(|(i, &child)| match &code.get(child)?.node {
+				Node::Operator(operator) => Some((i, operator.as_str())),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+
+		for (n, &(first, a)) in operators.iter().enumerate() {
+			for &(second, b) in &operators[n + 1..] {
+				if compare(a, b).is_none() {
+					ambiguities.push(Ambiguity {
+						group,
+						operators: (first, second),
+					});
+				}
+			}
+		}
+	}
+
+	ambiguities
+}
+
+/// The operator nodes taking part in an ambiguity.
+pub fn ambiguous(code: &Code) -> HashSet<NodeId> {
+	check(code)
+		.into_iter()
+		.flat_map(|ambiguity| {
+			let children = code.children(ambiguity.group);
+			[
+				children[ambiguity.operators.0],
+				children[ambiguity.operators.1],
+			]
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::projection::{parse, print};
+
+	/// The line of code written in `text`, with its operators recognized.
+	fn line(text: &str) -> (Code, NodeId) {
+		let code = parse(text).unwrap_or_else(|error| panic!("{error}"));
+		let line = code.children(code.root())[0];
+		(code, line)
+	}
+
+	#[test]
+	fn partial_order() {
+		assert_eq!(compare("*", "+"), Some(Ordering::Greater));
+		assert_eq!(compare("+", "*"), Some(Ordering::Less));
+		assert_eq!(compare("+", "-"), Some(Ordering::Equal));
+		// Through the levels in between.
+		assert_eq!(compare("*", "||"), Some(Ordering::Greater));
+		assert_eq!(compare("<", "&&"), Some(Ordering::Greater));
+		assert_eq!(compare("&&", "||"), None);
+		assert_eq!(compare("||", "&&"), None);
+		assert_eq!(compare("+", "plus"), None);
+	}
+
+	#[test]
+	fn mixing() {
+		let (code, _) = line("1 + size * 3 < 10 && ready\n");
+		assert!(check(&code).is_empty());
+
+		let (code, line) = line("a && b || c\n");
+		assert_eq!(
+			check(&code),
+			[Ambiguity {
+				group: line,
+				operators: (1, 3),
+			}]
+		);
+		assert_eq!(ambiguous(&code).len(), 2);
+	}
+
+	#[test]
+	fn fixing() {
+		for (text, range, fixed) in [
+			("a && b || c\n", 0..3, "(a && b) || c\n"),
+			("a || b && c\n", 0..3, "(a || b) && c\n"),
+			// The operands of tighter operators go in the brackets too.
+			("a < b && c || d\n", 0..5, "(a < b && c) || d\n"),
+			("x || a + b && c\n", 0..5, "(x || a + b) && c\n"),
+		] {
+			let (mut code, _) = line(text);
+			let [ambiguity] = check(&code)[..] else {
+				panic!("{text:?} isn't ambiguous once");
+			};
+			assert_eq!(ambiguity.fix(&code), range, "{text:?}");
+
+			code.wrap(ambiguity.group, ambiguity.fix(&code));
+			assert_eq!(print(&code), fixed);
+			assert!(check(&code).is_empty(), "{fixed:?}");
+		}
+	}
+}
@@ -0,0 +1,975 @@
+//! Exchange code with ordinary tools as indented plain text.
+//!
+//! Every line is a group of its words followed by the lines indented under
+//! it, and a line with a single word and nothing under it is just that word.
+//! Groups within a line are put in brackets, which is only done for the
+//! operands of operators, since line breaks already separate everything else,
+//! and for the first line of a group of nothing but lines, which has no words
+//! for its lines to go under.
+//!
+//! Names, literals, operators and types are written as they're shown and
+//! recognized again when read, so printing code and parsing it back gives
+//! the same code, apart from references the editor flags as broken and
+//! definitions outside of a binding.
+//!
+//! A node shown through aliases is annotated with a label like `[#1]`, and
+//! each alias is written as just `[=1]`.
+use crate::alias;
+use crate::binding;
+use crate::code::{Aspect, Code, Node, NodeId, Scope};
+use crate::implicit;
+use crate::library;
+use crate::member;
+use crate::precedence;
+use crate::types;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Writes the code as text.
+pub fn print(code: &Code) -> String {
+	// The nodes shown through aliases, numbered in the order they're written.
+	let mut labels = HashMap::new();
+	for node_id in code.walk(code.root()) {
+		if let Some(Ok(target)) = alias::target(code, node_id) {
+			let next = labels.len() + 1;
+			labels.entry(target).or_insert(next);
+		}
+	}
+
+	let mut text = String::new();
+	for &child in code.children(code.root()) {
+		print_line(code, &labels, child, 0, &mut text);
+	}
+	text
+}
+
+fn print_line(
+	code: &Code,
+	labels: &HashMap<NodeId, usize>,
+	node_id: NodeId,
+	depth: usize,
+	text: &mut String,
+) {
+	text.push_str(&"\t".repeat(depth));
+
+	let children = code.children(node_id);
+	let is_group = is_group_node(code, node_id);
+	// A group of nothing but lines has no words to put the lines under, so
+	// its first line is written in brackets in their place.
+	let head = head(code, node_id).max(1);
+	if !is_group || (head == 1 && children.len() == 1) || children.is_empty() {
+		text.push_str(&token(code, labels, node_id));
+		text.push('\n');
+		return;
+	}
+
+	let annotations = annotations(code, labels, node_id);
+	if !annotations.is_empty() {
+		text.push_str(&annotations);
+		text.push(' ');
+	}
+
+	let words = children[..head]
+		.iter()
+		.map(|&child| token(code, labels, child))
+		.collect::<Vec<_>>();
+	text.push_str(&words.join(" "));
+	text.push('\n');
+
+	for &child in &children[head..] {
+		print_line(code, labels, child, depth + 1, text);
+	}
+}
+
+/// How many children of a group are written on its line, up to the first
+/// group that isn't the operand of an operator. Everything after goes on
+/// lines of its own, so a group starting with such a group has nothing but
+/// lines.
+pub fn head(code: &Code, group: NodeId) -> usize {
+	let children = code.children(group);
+	let is_operator = |i: usize| {
+		children.get(i).is_some_and(|&child| {
+			matches!(
+				code.get(child).map(|placed| &placed.node),
+				Some(Node::Operator(_))
+			)
+		})
+	};
+
+	children
+		.iter()
+		.enumerate()
+		.position(|(i, &child)| {
+			is_group_node(code, child)
+				&& !(i.checked_sub(1).is_some_and(is_operator) || is_operator(i + 1))
+		})
+		.unwrap_or(children.len())
+}
+
+fn is_group_node(code: &Code, node_id: NodeId) -> bool {
+	matches!(
+		code.get(node_id).map(|placed| &placed.node),
+		Some(Node::Group(_))
+	)
+}
+
+/// A node written within a line.
+fn token(code: &Code, labels: &HashMap<NodeId, usize>, node_id: NodeId) -> String {
+	let Some(placed) = code.get(node_id) else {
+		return String::new();
+	};
+
+	let token = match &placed.node {
+		Node::Group(children) => {
+			let mut words = children
+				.iter()
+				.map(|&child| token(code, labels, child))
+				.collect::<Vec<_>>();
+
+			// Keep the group from being read as a converter.
+			if let [_, arrow, _] = &mut words[..] {
+				if arrow == "->" {
+					*arrow = quote(arrow);
+				}
+			}
+			format!("({})", words.join(" "))
+		}
+		Node::Text(string) => {
+			// Plain words would be read as names, operators or types.
+			if recognize(code, node_id).is_some() || !is_word(string) {
+				quote(string)
+			} else {
+				string.clone()
+			}
+		}
+		Node::Definition(name) | Node::Literal(name) => name.clone(),
+		Node::Reference(target) => code.name(*target).unwrap_or("?").into(),
+		Node::Operator(operator) | Node::Type(operator) | Node::Import(operator) => {
+			operator.clone()
+		}
+		Node::Converter { from, to } => format!("({from} -> {to})"),
+		Node::Member { object, member } => {
+			format!("{}.{member}", code.name(*object).unwrap_or("?"))
+		}
+		Node::Implicit(required) => format!("@{required}"),
+		// The label of the node it shows is written with the annotations.
+		Node::Alias(_) if alias_label(code, labels, node_id).is_some() => String::new(),
+		Node::Alias(_) => "?".into(),
+		Node::Pixel {
+			position: (x, y),
+			color,
+		} => format!("#{x},{y},{}", color_name(*color)),
+	};
+
+	annotations(code, labels, node_id) + &token
+}
+
+/// The label of the node an alias shows.
+fn alias_label(code: &Code, labels: &HashMap<NodeId, usize>, node_id: NodeId) -> Option<usize> {
+	let target = alias::target(code, node_id)?.ok()?;
+	labels.get(&target).copied()
+}
+
+/// The aspects, scope and alias labels of a node, like `[docs,isolating]`.
+fn annotations(code: &Code, labels: &HashMap<NodeId, usize>, node_id: NodeId) -> String {
+	let Some(placed) = code.get(node_id) else {
+		return String::new();
+	};
+
+	let mut names = Aspect::ALL
+		.iter()
+		.filter(|aspect| placed.aspects.contains(aspect))
+		.map(|aspect| aspect.name().replace(' ', "_"))
+		.collect::<Vec<_>>();
+	if node_id != code.root() {
+		names.extend(placed.scope.map(|scope| scope.name().to_owned()));
+	}
+	names.extend(labels.get(&node_id).map(|label| format!("#{label}")));
+	names.extend(alias_label(code, labels, node_id).map(|label| format!("={label}")));
+
+	if names.is_empty() {
+		String::new()
+	} else {
+		format!("[{}]", names.join(","))
+	}
+}
+
+/// Whether a text can be written as it is.
+fn is_word(string: &str) -> bool {
+	!string.is_empty()
+		&& !string.starts_with(['[', '#', '`', '"'])
+		&& !string.contains(|c: char| c.is_whitespace() || c == '(' || c == ')')
+}
+
+fn quote(string: &str) -> String {
+	let mut quoted = String::from('`');
+	for c in string.chars() {
+		if c == '`' || c == '\\' {
+			quoted.push('\\');
+		}
+		quoted.push(c);
+	}
+	quoted.push('`');
+	quoted
+}
+
+fn color_name(color: crate::code::Color) -> &'static str {
+	match color {
+		crate::code::Color::Red => "red",
+		crate::code::Color::Green => "green",
+		crate::code::Color::Blue => "blue",
+	}
+}
+
+/// Something that keeps a text from being read as code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+	pub line: usize,
+	pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+	/// A line is indented by more than one level more than the one before.
+	Indentation,
+	/// Lines are indented with something other than tabs.
+	Spaces,
+	UnclosedGroup,
+	UnexpectedClose,
+	UnclosedQuote,
+	UnknownAnnotation(String),
+	InvalidPixel(String),
+	/// An alias shows a label that isn't on any node.
+	UnknownLabel(usize),
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "line {}: ", self.line)?;
+		match &self.kind {
+			ParseErrorKind::Indentation => write!(f, "indented by more than one level"),
+			ParseErrorKind::Spaces => write!(f, "expected tabs for indentation"),
+			ParseErrorKind::UnclosedGroup => write!(f, "expected `)`"),
+			ParseErrorKind::UnexpectedClose => write!(f, "unexpected `)`"),
+			ParseErrorKind::UnclosedQuote => write!(f, "expected a closing quote"),
+			ParseErrorKind::UnknownAnnotation(name) => write!(f, "unknown annotation `{name}`"),
+			ParseErrorKind::InvalidPixel(pixel) => {
+				write!(f, "`{pixel}` is not a pixel like `#0,0,red`")
+			}
+			ParseErrorKind::UnknownLabel(label) => write!(f, "no node is labeled `#{label}`"),
+		}
+	}
+}
+
+/// A node read from a line, before it's put into the code.
+struct Item {
+	annotations: Annotations,
+	kind: ItemKind,
+}
+
+enum ItemKind {
+	Group(Vec<Item>),
+	/// A word that's tied to a definition or recognized as an operator.
+	Word(String),
+	/// A node taken as it is.
+	Leaf(Node),
+	/// An alias of the node with a label, which may not be read yet.
+	Alias(usize),
+}
+
+#[derive(Default)]
+struct Annotations {
+	aspects: HashSet<Aspect>,
+	scope: Option<Scope>,
+	/// The label aliases refer to the node by.
+	label: Option<usize>,
+	/// The label of the node an alias shows, which makes the item an alias.
+	alias: Option<usize>,
+}
+
+/// The nodes that are fixed up once all of them are in the code.
+#[derive(Default)]
+struct Inserted {
+	words: Vec<NodeId>,
+	labels: HashMap<usize, NodeId>,
+	/// The placeholders standing in for aliases, with the label of the node
+	/// each one shows and the line it's on.
+	aliases: Vec<(NodeId, usize, usize)>,
+}
+
+/// A line with its indentation and the lines indented under it.
+struct Line {
+	number: usize,
+	items: Vec<Item>,
+	annotations: Annotations,
+	block: Vec<Line>,
+}
+
+/// Reads code written by [`print`], or by hand in the same way. Empty lines
+/// are skipped.
+pub fn parse(text: &str) -> Result<Code, ParseError> {
+	let mut lines: Vec<(usize, Line)> = Vec::new();
+
+	for (i, content) in text.lines().enumerate() {
+		let number = i + 1;
+		if content.trim().is_empty() {
+			continue;
+		}
+
+		let depth = content.chars().take_while(|&c| c == '\t').count();
+		let content = &content[depth..];
+		if content.starts_with(char::is_whitespace) {
+			return Err(ParseError {
+				line: number,
+				kind: ParseErrorKind::Spaces,
+			});
+		}
+
+		let previous = lines.last().map_or(0, |&(depth, _)| depth + 1);
+		if depth > previous {
+			return Err(ParseError {
+				line: number,
+				kind: ParseErrorKind::Indentation,
+			});
+		}
+
+		let (annotations, items) =
+			parse_line(content).map_err(|kind| ParseError { line: number, kind })?;
+		lines.push((
+			depth,
+			Line {
+				number,
+				items,
+				annotations,
+				block: Vec::new(),
+			},
+		));
+	}
+
+	// Nest every line under the closest line before it that's less indented.
+	let mut stack: Vec<(usize, Line)> = Vec::new();
+	let mut top = Vec::new();
+	for (depth, line) in lines {
+		close_lines(&mut stack, &mut top, depth);
+		stack.push((depth, line));
+	}
+	close_lines(&mut stack, &mut top, 0);
+
+	let mut code = Code::new();
+	let mut inserted = Inserted::default();
+	let root = code.root();
+	for line in top {
+		insert_line(&mut code, line, root, &mut inserted);
+	}
+
+	link(&mut code, &inserted.words);
+	for &(placeholder, label, line) in &inserted.aliases {
+		let Some(&target) = inserted.labels.get(&label) else {
+			return Err(ParseError {
+				line,
+				kind: ParseErrorKind::UnknownLabel(label),
+			});
+		};
+		if let Some(placed) = code.get_mut(placeholder) {
+			placed.node = Node::Alias(target);
+		}
+	}
+	Ok(code)
+}
+
+/// Moves the lines indented at least `depth` levels under their parents.
+fn close_lines(stack: &mut Vec<(usize, Line)>, top: &mut Vec<Line>, depth: usize) {
+	while stack.last().is_some_and(|&(last, _)| last >= depth) {
+		let (_, line) = stack.pop().unwrap();
+		match stack.last_mut() {
+			Some((_, parent)) => parent.block.push(line),
+			None => top.push(line),
+		}
+	}
+}
+
+fn insert_line(code: &mut Code, line: Line, parent: NodeId, inserted: &mut Inserted) {
+	let Line {
+		number,
+		mut items,
+		annotations,
+		block,
+	} = line;
+
+	if items.len() == 1 && block.is_empty() && is_empty(&annotations) {
+		insert_item(code, items.pop().unwrap(), parent, number, inserted);
+		return;
+	}
+
+	let Some(group) = code.insert(Node::Group(Vec::new()), parent) else {
+		return;
+	};
+	annotate(code, group, annotations, inserted);
+
+	for item in items {
+		insert_item(code, item, group, number, inserted);
+	}
+	for line in block {
+		insert_line(code, line, group, inserted);
+	}
+}
+
+fn insert_item(code: &mut Code, item: Item, parent: NodeId, line: usize, inserted: &mut Inserted) {
+	let node_id = match item.kind {
+		ItemKind::Group(items) => {
+			let Some(group) = code.insert(Node::Group(Vec::new()), parent) else {
+				return;
+			};
+			for item in items {
+				insert_item(code, item, group, line, inserted);
+			}
+			group
+		}
+		ItemKind::Word(word) => {
+			let Some(node_id) = code.insert(Node::Text(word), parent) else {
+				return;
+			};
+			inserted.words.push(node_id);
+			node_id
+		}
+		ItemKind::Leaf(node) => {
+			let Some(node_id) = code.insert(node, parent) else {
+				return;
+			};
+			node_id
+		}
+		ItemKind::Alias(label) => {
+			// The labeled node may come later, so an empty group holds the
+			// alias's place until every node is in the code.
+			let Some(placeholder) = code.insert(Node::Group(Vec::new()), parent) else {
+				return;
+			};
+			inserted.aliases.push((placeholder, label, line));
+			placeholder
+		}
+	};
+
+	annotate(code, node_id, item.annotations, inserted);
+}
+
+fn annotate(code: &mut Code, node_id: NodeId, annotations: Annotations, inserted: &mut Inserted) {
+	if let Some(label) = annotations.label {
+		inserted.labels.insert(label, node_id);
+	}
+	if let Some(placed) = code.get_mut(node_id) {
+		placed.aspects = annotations.aspects;
+		placed.scope = annotations.scope;
+	}
+}
+
+fn is_empty(annotations: &Annotations) -> bool {
+	annotations.aspects.is_empty()
+		&& annotations.scope.is_none()
+		&& annotations.label.is_none()
+		&& annotations.alias.is_none()
+}
+
+/// The node a word is read as, like it would be once typed.
+fn recognize(code: &Code, node_id: NodeId) -> Option<Node> {
+	types::literal(code, node_id)
+		.or_else(|| binding::link(code, node_id))
+		.or_else(|| precedence::operator(code, node_id))
+		.or_else(|| types::node(code, node_id))
+		.or_else(|| library::import(code, node_id))
+		.or_else(|| member::access(code, node_id))
+		.or_else(|| implicit::declare(code, node_id))
+}
+
+/// Ties the words to what they name, definitions first so references can be
+/// made to definitions anywhere in an overwriting context.
+fn link(code: &mut Code, words: &[NodeId]) {
+	for definitions in [true, false] {
+		for &word in words {
+			let node = recognize(code, word);
+			let is_definition = matches!(node, Some(Node::Definition(_)));
+
+			if let (Some(node), true) = (node, is_definition == definitions) {
+				if let Some(placed) = code.get_mut(word) {
+					placed.node = node;
+				}
+			}
+		}
+	}
+}
+
+/// Reads the annotations of a line and the items on it.
+fn parse_line(content: &str) -> Result<(Annotations, Vec<Item>), ParseErrorKind> {
+	let mut chars = content.chars().peekable();
+
+	// Annotations followed by a space belong to the whole line.
+	let mut annotations = Annotations::default();
+	if chars.peek() == Some(&'[') {
+		let mut lookahead = chars.clone();
+		let line_annotations = parse_annotations(&mut lookahead)?;
+		let is_alias = line_annotations.alias.is_some();
+		if !is_alias && lookahead.peek().is_none_or(|c| c.is_whitespace()) {
+			annotations = line_annotations;
+			chars = lookahead;
+		}
+	}
+
+	let items = parse_items(&mut chars)?;
+	match chars.next() {
+		Some(')') => Err(ParseErrorKind::UnexpectedClose),
+		_ => Ok((annotations, items)),
+	}
+}
+
+/// Reads items up to the end of the line or a `)`, which is left unread.
+fn parse_items(chars: &mut Peekable<Chars>) -> Result<Vec<Item>, ParseErrorKind> {
+	let mut items = Vec::new();
+
+	loop {
+		while chars.peek().is_some_and(|c| c.is_whitespace()) {
+			chars.next();
+		}
+
+		match chars.peek() {
+			None | Some(')') => return Ok(items),
+			Some(_) => items.push(parse_item(chars)?),
+		}
+	}
+}
+
+fn parse_item(chars: &mut Peekable<Chars>) -> Result<Item, ParseErrorKind> {
+	let annotations = if chars.peek() == Some(&'[') {
+		parse_annotations(chars)?
+	} else {
+		Annotations::default()
+	};
+
+	// An alias is written as just its annotations.
+	if let Some(label) = annotations.alias {
+		let kind = ItemKind::Alias(label);
+		return Ok(Item { annotations, kind });
+	}
+
+	let kind = match chars.peek() {
+		Some('(') => {
+			chars.next();
+			let items = parse_items(chars)?;
+			if chars.next() != Some(')') {
+				return Err(ParseErrorKind::UnclosedGroup);
+			}
+			converter(&items).unwrap_or(ItemKind::Group(items))
+		}
+		Some('`') => {
+			chars.next();
+			let mut string = String::new();
+			loop {
+				match chars.next() {
+					Some('`') => break,
+					Some('\\') => string.extend(chars.next()),
+					Some(c) => string.push(c),
+					None => return Err(ParseErrorKind::UnclosedQuote),
+				}
+			}
+			ItemKind::Leaf(Node::Text(string))
+		}
+		Some('"') => {
+			let mut string = String::from(chars.next().unwrap());
+			loop {
+				match chars.next() {
+					Some('"') => break,
+					Some(c) => string.push(c),
+					None => return Err(ParseErrorKind::UnclosedQuote),
+				}
+			}
+			string.push('"');
+			ItemKind::Leaf(Node::Literal(string))
+		}
+		Some('#') => {
+			let word = parse_word(chars);
+			ItemKind::Leaf(parse_pixel(&word).ok_or(ParseErrorKind::InvalidPixel(word))?)
+		}
+		_ => ItemKind::Word(parse_word(chars)),
+	};
+
+	Ok(Item { annotations, kind })
+}
+
+/// Reads the items of a group like `(Str -> Int)` as a converter.
+fn converter(items: &[Item]) -> Option<ItemKind> {
+	let mut words = items.iter().map(|item| match &item.kind {
+		ItemKind::Word(word) if is_empty(&item.annotations) => Some(word.as_str()),
+		_ => None,
+	});
+
+	let (Some(Some(from)), Some(Some("->")), Some(Some(to)), None) =
+		(words.next(), words.next(), words.next(), words.next())
+	else {
+		return None;
+	};
+	(binding::is_name(from) && binding::is_name(to)).then(|| {
+		ItemKind::Leaf(Node::Converter {
+			from: from.into(),
+			to: to.into(),
+		})
+	})
+}
+
+fn parse_word(chars: &mut Peekable<Chars>) -> String {
+	let mut word = String::new();
+	while let Some(&c) = chars.peek() {
+		if c.is_whitespace() || c == '(' || c == ')' {
+			break;
+		}
+		word.push(c);
+		chars.next();
+	}
+	word
+}
+
+fn parse_annotations(chars: &mut Peekable<Chars>) -> Result<Annotations, ParseErrorKind> {
+	chars.next();
+	let mut list = String::new();
+	loop {
+		match chars.next() {
+			Some(']') => break,
+			Some(c) => list.push(c),
+			None => return Err(ParseErrorKind::UnknownAnnotation(list)),
+		}
+	}
+
+	let mut annotations = Annotations::default();
+	for name in list.split(',').map(str::trim) {
+		if let Some(&aspect) = Aspect::ALL
+			.iter()
+			.find(|aspect| aspect.name().replace(' ', "_") == name)
+		{
+			annotations.aspects.insert(aspect);
+		} else if let Some(scope) = [Scope::Additive, Scope::Overwriting, Scope::Isolating]
+			.into_iter()
+			.find(|scope| scope.name() == name)
+		{
+			annotations.scope = Some(scope);
+		} else if let Some(label) = name.strip_prefix('#').and_then(|label| label.parse().ok()) {
+			annotations.label = Some(label);
+		} else if let Some(label) = name.strip_prefix('=').and_then(|label| label.parse().ok()) {
+			annotations.alias = Some(label);
+		} else {
+			return Err(ParseErrorKind::UnknownAnnotation(name.into()));
+		}
+	}
+	Ok(annotations)
+}
+
+/// Reads a pixel like `#0,1,red`.
+fn parse_pixel(word: &str) -> Option<Node> {
+	let mut parts = word.strip_prefix('#')?.split(',');
+	let x = parts.next()?.parse().ok()?;
+	let y = parts.next()?.parse().ok()?;
+	let color = match parts.next()? {
+		"red" => crate::code::Color::Red,
+		"green" => crate::code::Color::Green,
+		"blue" => crate::code::Color::Blue,
+		_ => return None,
+	};
+
+	parts.next().is_none().then_some(Node::Pixel {
+		position: (x, y),
+		color,
+	})
+}
+
+/// Whether two trees are the same apart from their node ids, which is what
+/// printing and parsing keeps.
+pub fn equivalent(a: &Code, b: &Code) -> bool {
+	let (nodes_a, nodes_b) = (a.walk(a.root()), b.walk(b.root()));
+	if nodes_a.len() != nodes_b.len() {
+		return false;
+	}
+
+	let ids = nodes_a
+		.iter()
+		.copied()
+		.zip(nodes_b.iter().copied())
+		.collect::<HashMap<_, _>>();
+
+	nodes_a.iter().zip(&nodes_b).all(|(node_a, node_b)| {
+		let (Some(placed_a), Some(placed_b)) = (a.get(*node_a), b.get(*node_b)) else {
+			return false;
+		};
+
+		let same_node = match (&placed_a.node, &placed_b.node) {
+			(Node::Group(children_a), Node::Group(children_b)) => children_a
+				.iter()
+				.map(|child| ids.get(child))
+				.eq(children_b.iter().map(Some)),
+			(Node::Text(a), Node::Text(b)) => a == b,
+			(Node::Definition(a), Node::Definition(b)) => a == b,
+			(Node::Reference(target_a), Node::Reference(target_b))
+			| (Node::Alias(target_a), Node::Alias(target_b)) => ids.get(target_a) == Some(target_b),
+			(
+				Node::Member {
+					object: object_a,
+					member: member_a,
+				},
+				Node::Member {
+					object: object_b,
+					member: member_b,
+				},
+			) => ids.get(object_a) == Some(object_b) && member_a == member_b,
+			(Node::Operator(a), Node::Operator(b))
+			| (Node::Literal(a), Node::Literal(b))
+			| (Node::Type(a), Node::Type(b))
+			| (Node::Import(a), Node::Import(b))
+			| (Node::Implicit(a), Node::Implicit(b)) => a == b,
+			(
+				Node::Converter {
+					from: from_a,
+					to: to_a,
+				},
+				Node::Converter {
+					from: from_b,
+					to: to_b,
+				},
+			) => from_a == from_b && to_a == to_b,
+			(
+				Node::Pixel {
+					position: position_a,
+					color: color_a,
+				},
+				Node::Pixel {
+					position: position_b,
+					color: color_b,
+				},
+			) => position_a == position_b && color_a == color_b,
+			_ => false,
+		};
+
+		same_node
+			&& placed_a.aspects == placed_b.aspects
+			&& (placed_a.scope == placed_b.scope || *node_a == a.root())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::code::Color;
+
+	/// Parses the printed code back, checking it's the same code and that
+	/// printing it again gives the same text.
+	fn round_trip(code: &Code) -> Code {
+		let text = print(code);
+		let parsed = parse(&text).unwrap_or_else(|error| panic!("{error}\n{text}"));
+		assert!(equivalent(code, &parsed), "not the same code:\n{text}");
+		assert_eq!(print(&parsed), text);
+		parsed
+	}
+
+	fn parse_ok(text: &str) -> Code {
+		parse(text).unwrap_or_else(|error| panic!("{error}"))
+	}
+
+	fn error(text: &str) -> ParseErrorKind {
+		match parse(text) {
+			Ok(_) => panic!("parsed {text:?}"),
+			Err(error) => error.kind,
+		}
+	}
+
+	fn nodes(code: &Code) -> Vec<&Node> {
+		code.walk(code.root())
+			.into_iter()
+			.filter_map(|node_id| code.get(node_id).map(|placed| &placed.node))
+			.collect()
+	}
+
+	#[test]
+	fn examples() {
+		let code = parse_ok(include_str!("../code.txt"));
+		round_trip(&code);
+		round_trip(&round_trip(&code));
+	}
+
+	#[test]
+	fn indented_lines() {
+		let code = parse_ok("a b\n\tc d\n\t\te\nf\n");
+		// A single word on a line of its own is the same as one at the end
+		// of the line it's indented under.
+		assert_eq!(print(&code), "a b\n\tc d e\nf\n");
+		round_trip(&code);
+	}
+
+	#[test]
+	fn blocks() {
+		// Line breaks already separate the lines of a block, so none of
+		// them are put in brackets.
+		for text in [
+			"greet = @Console\n\tConsole print \"hello\"\n",
+			"array =\n\tlength = 3\n\tlast =\n\t\tlength - 1\nprint array.last\n",
+			"a b\n\tc d\n\te f\n\t\tg h\n\ti j\n",
+		] {
+			assert_eq!(print(&parse_ok(text)), text);
+		}
+	}
+
+	#[test]
+	fn sample() {
+		let code = crate::sample();
+		assert_eq!(
+			print(&code),
+			"pixels\n\
+			#0,0,red #0,1,red\n\
+			#1,0,red #1,1,red\n\
+			[docs]`a grid of pixels, row by row`\n\
+			size = 2\n\
+			size := 4\n\
+			(Rng -> Int) : next\n\
+			greet = @Console\n\
+			\tConsole print [#1]\"hello\"\n\
+			greet\n\
+			Console print [=1]\n\
+			array =\n\
+			\tlength = 3\n\
+			\tlast =\n\
+			\t\tlength - 1\n\
+			print array.length array.last\n\
+			Console print size Int\n\
+			1 + size * 3 < 10 && true || false\n"
+		);
+		round_trip(&code);
+	}
+
+	#[test]
+	fn quoting() {
+		let mut code = Code::new();
+		let root = code.root();
+		for string in [
+			"two words",
+			"`quoted`",
+			"back\\slash",
+			"",
+			"(",
+			"#1",
+			"[docs]",
+		] {
+			code.insert(Node::Text(string.into()), root);
+		}
+		// Would be read as a type, an operator or literals if written plainly.
+		code.insert(Node::Text("Console".into()), root);
+		code.insert(Node::Text("+".into()), root);
+		code.insert(Node::Text("3".into()), root);
+		code.insert(Node::Text("\"hello\"".into()), root);
+		code.insert(Node::Text("\"unclosed".into()), root);
+
+		let parsed = round_trip(&code);
+		assert!(nodes(&parsed)
+			.iter()
+			.skip(1)
+			.all(|node| matches!(node, Node::Text(_))));
+	}
+
+	#[test]
+	fn annotations() {
+		let code = parse_ok("[docs,isolating] a [notes]b\n\t[error_handling]c\n");
+		let line = code.children(code.root())[0];
+		let placed = code.get(line).unwrap();
+		assert!(placed.aspects.contains(&Aspect::Docs));
+		assert!(placed.scope == Some(Scope::Isolating));
+
+		let b = code.children(line)[1];
+		assert!(code.get(b).unwrap().aspects.contains(&Aspect::Notes));
+		round_trip(&code);
+
+		assert_eq!(
+			error("[shiny] a\n"),
+			ParseErrorKind::UnknownAnnotation("shiny".into())
+		);
+	}
+
+	#[test]
+	fn pixels() {
+		let code = parse_ok("#0,1,red #2,3,blue\n");
+		assert!(nodes(&code).iter().any(|node| matches!(
+			node,
+			Node::Pixel {
+				position: (2, 3),
+				color: Color::Blue
+			}
+		)));
+		round_trip(&code);
+
+		assert_eq!(
+			error("#0,red\n"),
+			ParseErrorKind::InvalidPixel("#0,red".into())
+		);
+	}
+
+	#[test]
+	fn node_kinds() {
+		let code = parse_ok(
+			"\
+size = 2
+size := size + 1 * 2
+(Rng -> Int) : next
+Console print size Int
+std/HashMap
+greet =
+	@Console
+	Console print `hello` \"hello world\"
+greet
+array =
+	length = 3
+print array.length
+",
+		);
+		let nodes = nodes(&code);
+		let count = |kind: fn(&Node) -> bool| nodes.iter().filter(|node| kind(node)).count();
+		assert_eq!(count(|node| matches!(node, Node::Definition(_))), 4);
+		assert_eq!(count(|node| matches!(node, Node::Reference(_))), 4);
+		assert_eq!(count(|node| matches!(node, Node::Operator(_))), 2);
+		assert_eq!(count(|node| matches!(node, Node::Converter { .. })), 1);
+		assert_eq!(count(|node| matches!(node, Node::Type(_))), 3);
+		assert_eq!(count(|node| matches!(node, Node::Import(_))), 1);
+		assert_eq!(count(|node| matches!(node, Node::Implicit(_))), 1);
+		assert_eq!(count(|node| matches!(node, Node::Member { .. })), 1);
+		assert_eq!(count(|node| matches!(node, Node::Literal(_))), 5);
+		round_trip(&code);
+	}
+
+	#[test]
+	fn aliases() {
+		let code = parse_ok("[#1]`hello` world\nprint [=1]\n[=1]\n");
+		let target = code.children(code.children(code.root())[0])[0];
+		let aliases = nodes(&code)
+			.into_iter()
+			.filter(|node| matches!(node, Node::Alias(alias) if *alias == target))
+			.count();
+		assert_eq!(aliases, 2);
+		round_trip(&code);
+
+		// An alias can show a node labeled further down.
+		let code = parse_ok("[=1] x\n[#1]a b\n");
+		let target = code.children(code.children(code.root())[1])[0];
+		assert!(nodes(&code)
+			.into_iter()
+			.any(|node| matches!(node, Node::Alias(alias) if *alias == target)));
+		round_trip(&code);
+
+		assert_eq!(error("[=3]\n"), ParseErrorKind::UnknownLabel(3));
+		assert_eq!(
+			parse("a\n\tb [=2]\n").err().map(|error| error.line),
+			Some(2)
+		);
+	}
+
+	#[test]
+	fn errors() {
+		assert_eq!(error("a\n\t\tb\n"), ParseErrorKind::Indentation);
+		assert_eq!(error("a\n  b\n"), ParseErrorKind::Spaces);
+		assert_eq!(error("(a b\n"), ParseErrorKind::UnclosedGroup);
+		assert_eq!(error("a b)\n"), ParseErrorKind::UnexpectedClose);
+		assert_eq!(error("`a\n"), ParseErrorKind::UnclosedQuote);
+		assert_eq!(error("\"a\n"), ParseErrorKind::UnclosedQuote);
+	}
+}
@@ -0,0 +1,201 @@
+//! Work out which identifiers are available where.
+use crate::code::{Code, Node, NodeId, Scope};
+use std::collections::{HashMap, HashSet};
+
+/// The definitions available at a node by name.
+pub type Visible<'a> = HashMap<&'a str, NodeId>;
+
+/// The definitions available at a node, going through the contexts around it
+/// from the outermost in.
+///
+/// Groups without a scope of their own, like the line of a binding, make
+/// their definitions in the closest enclosing context.
+pub fn visible(code: &Code, node_id: NodeId) -> Visible<'_> {
+	available(code, node_id)
+		.into_iter()
+		.filter_map(|definition| Some((code.name(definition)?, definition)))
+		.collect()
+}
+
+/// The converters available at a node, from the outermost context in.
+pub fn converters(code: &Code, node_id: NodeId) -> Vec<NodeId> {
+	available(code, node_id)
+		.into_iter()
+		.filter(|&definition| {
+			matches!(
+				code.get(definition).map(|placed| &placed.node),
+				Some(Node::Converter { .. })
+			)
+		})
+		.collect()
+}
+
+/// The definitions available at a node, from the outermost context in, so
+/// later ones take the place of earlier ones of the same name.
+fn available(code: &Code, node_id: NodeId) -> Vec<NodeId> {
+	let order = code
+		.walk(code.root())
+		.into_iter()
+		.enumerate()
+		.map(|(i, node_id)| (node_id, i))
+		.collect::<HashMap<_, _>>();
+	let position = order.get(&node_id).copied().unwrap_or(usize::MAX);
+
+	let mut available = Vec::new();
+	let path = code.path(node_id);
+
+	for &context in &path[..path.len().saturating_sub(1)] {
+		let Some(scope) = code.get(context).and_then(|placed| placed.scope) else {
+			continue;
+		};
+
+		if scope == Scope::Isolating {
+			available.clear();
+		}
+
+		for definition in definitions(code, context) {
+			let made_before = order.get(&definition).is_some_and(|&i| i < position);
+			if scope == Scope::Overwriting || made_before {
+				available.push(definition);
+			}
+		}
+	}
+
+	available
+}
+
+/// The definitions a context makes, in reading order, leaving out the ones
+/// made by the contexts inside it.
+fn definitions(code: &Code, context: NodeId) -> Vec<NodeId> {
+	let mut definitions = Vec::new();
+	let mut stack = code
+		.children(context)
+		.iter()
+		.rev()
+		.copied()
+		.collect::<Vec<_>>();
+
+	while let Some(node_id) = stack.pop() {
+		let Some(placed) = code.get(node_id) else {
+			continue;
+		};
+
+		match &placed.node {
+			Node::Definition(_) | Node::Converter { .. } | Node::Import(_) => {
+				definitions.push(node_id)
+			}
+			Node::Group(children) if placed.scope.is_none() => {
+				stack.extend(children.iter().rev());
+			}
+			_ => {}
+		}
+	}
+
+	definitions
+}
+
+/// The references to definitions that exist but aren't available where
+/// they're used.
+pub fn out_of_scope(code: &Code) -> HashSet<NodeId> {
+	code.walk(code.root())
+		.into_iter()
+		.filter(
+			|&node_id| match code.get(node_id).map(|placed| &placed.node) {
+				Some(Node::Reference(target)) => code
+					.name(*target)
+					.is_some_and(|name| visible(code, node_id).get(name) != Some(target)),
+				_ => false,
+			},
+		)
+		.collect()
+}
+
+/// The names available at a node that start with what was typed into it,
+/// sorted.
+pub fn completions(code: &Code, node_id: NodeId) -> Vec<&str> {
+	let typed = match code.get(node_id).map(|placed| &placed.node) {
+		Some(Node::Text(typed)) => typed.trim(),
+		_ => return Vec::new(),
+	};
+
+	let mut names = visible(code, node_id)
+		.into_keys()
+		.filter(|name| name.starts_with(typed) && *name != typed)
+		.collect::<Vec<_>>();
+	names.sort_unstable();
+	names
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::projection::parse;
+
+	fn parse_ok(text: &str) -> Code {
+		parse(text).unwrap_or_else(|error| panic!("{error}"))
+	}
+
+	/// The first word in the code that was left as typed, like a name that
+	/// isn't available.
+	fn word(code: &Code, text: &str) -> NodeId {
+		code.walk(code.root())
+			.into_iter()
+			.find(|&node_id| {
+				matches!(
+					code.get(node_id).map(|placed| &placed.node),
+					Some(Node::Text(typed)) if typed == text
+				)
+			})
+			.unwrap_or_else(|| panic!("no word {text:?}"))
+	}
+
+	/// The definitions of a name, in reading order.
+	fn definitions_of(code: &Code, name: &str) -> Vec<NodeId> {
+		code.walk(code.root())
+			.into_iter()
+			.filter(|&node_id| {
+				matches!(
+					code.get(node_id).map(|placed| &placed.node),
+					Some(Node::Definition(defined)) if defined == name
+				)
+			})
+			.collect()
+	}
+
+	fn names(visible: Visible<'_>) -> Vec<&str> {
+		let mut names = visible.into_keys().collect::<Vec<_>>();
+		names.sort_unstable();
+		names
+	}
+
+	#[test]
+	fn additive() {
+		let code = parse_ok("x = 1\n[additive] a = b\n\ty = 2\n\tc\n");
+		// The enclosing definitions and the ones made so far, but not the
+		// ones made later on.
+		assert_eq!(names(visible(&code, word(&code, "b"))), ["a", "x"]);
+		assert_eq!(names(visible(&code, word(&code, "c"))), ["a", "x", "y"]);
+	}
+
+	#[test]
+	fn overwriting() {
+		let code = parse_ok("c = 0\n[overwriting] a b\n\tc = 1\nd\n");
+		let [outer, inner] = definitions_of(&code, "c")[..] else {
+			panic!("not two definitions of c");
+		};
+		// Made later, but available everywhere in the context, in place of
+		// the enclosing one.
+		assert_eq!(visible(&code, word(&code, "a")).get("c"), Some(&inner));
+		assert_eq!(visible(&code, word(&code, "b")).get("c"), Some(&inner));
+		assert_eq!(visible(&code, word(&code, "d")).get("c"), Some(&outer));
+	}
+
+	#[test]
+	fn isolating() {
+		let code = parse_ok("x = 1\n[isolating] a\n\ty = 2\n\tb\nz\n");
+		assert!(visible(&code, word(&code, "a")).is_empty());
+		assert_eq!(names(visible(&code, word(&code, "b"))), ["y"]);
+		// Its definitions stay inside it.
+		assert_eq!(names(visible(&code, word(&code, "z"))), ["x"]);
+	}
+}
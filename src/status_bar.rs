@@ -0,0 +1,206 @@
+//! Tell where the selection is and what state the editor is in.
+use crate::alias;
+use crate::aspect::AspectFilter;
+use crate::code::{Aspect, Code, Node, NodeId};
+use crate::document::Document;
+use crate::implicit;
+use crate::library::Libraries;
+use crate::member;
+use crate::mode::Mode;
+use crate::scope;
+use crate::style;
+use crate::theme::Theme;
+use crate::types;
+use crate::visual_code::Message;
+use iced::widget::{container, mouse_area, row, text, Space};
+use iced::{Alignment, Color, Element, Length};
+
+/// How many characters of a text node a breadcrumb shows.
+const LABEL_LENGTH: usize = 24;
+
+/// How many of the names completing the typed one are listed.
+const COMPLETIONS: usize = 5;
+
+/// Shows the path from the root to the selected node, each step of which
+/// selects that node when clicked, next to the editor's state.
+pub fn view<'a>(
+	document: &'a Document,
+	mode: Mode,
+	aspects: &AspectFilter,
+	libraries: &Libraries,
+	error: Option<&'a str>,
+	theme: &Theme,
+) -> Element<'a, Message> {
+	let code = &document.code;
+	let path = document
+		.visual
+		.selected()
+		.map_or_else(|| vec![code.root()], |selected| code.path(selected));
+
+	let mut breadcrumbs = row![].spacing(4).align_items(Alignment::Center);
+	for (i, &node_id) in path.iter().enumerate() {
+		if i > 0 {
+			breadcrumbs = breadcrumbs.push(text("›").size(14).style(faded(theme.text, 0.5)));
+		}
+
+		let label = if node_id == code.root() {
+			document.name.clone()
+		} else {
+			label(code, node_id)
+		};
+		breadcrumbs = breadcrumbs.push(
+			mouse_area(container(text(label).size(14)).padding([2, 4]))
+				.on_press(Message::Select(Some(node_id))),
+		);
+	}
+
+	let aspects = row(Aspect::ALL.iter().filter_map(|&aspect| {
+		let opacity = aspects.visibility(aspect).opacity();
+		(opacity > 0.0).then(|| {
+			text(aspect.name())
+				.size(14)
+				.style(faded(theme.text, opacity))
+				.into()
+		})
+	}))
+	.spacing(12);
+
+	let completions = document
+		.visual
+		.selected()
+		.filter(|_| mode == Mode::Insert)
+		.map(|node_id| {
+			scope::completions(code, node_id)
+				.into_iter()
+				.map(String::from)
+				.chain(libraries.completions(code, node_id))
+				.collect::<Vec<_>>()
+		})
+		.filter(|names| !names.is_empty())
+		.map(|names| {
+			let names = names[..names.len().min(COMPLETIONS)].join(", ");
+			text(format!("tab: {names}"))
+				.size(14)
+				.style(faded(theme.text, 0.6))
+		});
+	// What the selected type, import or member stands for, or what fills in
+	// the implicit inputs of the selected call. Aliases only show why they
+	// can't show their node.
+	let resolution = document
+		.visual
+		.selected()
+		.and_then(|node_id| match &code.get(node_id)?.node {
+			Node::Type(required) => Some(
+				types::resolve(code, node_id, required)
+					.map(|provider| provider.describe(code))
+					.map_err(|error| error.to_string()),
+			),
+			Node::Import(short) => {
+				Some(libraries.resolve(short).map_err(|error| error.to_string()))
+			}
+			Node::Reference(_) => {
+				let arguments = implicit::arguments(code, node_id);
+				let described = arguments
+					.iter()
+					.map(|(required, provider)| match provider {
+						Ok(provider) => Ok(provider.describe(code)),
+						Err(_) => Err(format!("nothing provides implicit {required}")),
+					})
+					.collect::<Result<Vec<_>, _>>();
+				(!arguments.is_empty()).then(|| {
+					described.map(|described| format!("implicit: {}", described.join(", ")))
+				})
+			}
+			Node::Member { .. } => member::resolve(code, node_id).map(|member| {
+				member
+					.map(|member| member.kind.name().to_owned())
+					.map_err(|error| error.to_string())
+			}),
+			Node::Alias(_) => alias::target(code, node_id)?
+				.err()
+				.map(|error| Err(error.to_string())),
+			_ => None,
+		})
+		.map(|resolution| match resolution {
+			Ok(resolution) => text(resolution).size(14).style(faded(theme.text, 0.6)),
+			Err(error) => text(error).size(14).style(theme.error),
+		});
+	let error = error.map(|error| text(error).size(14).style(theme.error));
+	let dirty = document
+		.is_dirty()
+		.then(|| text("modified").size(14).style(faded(theme.text, 0.6)));
+
+	container(
+		row![breadcrumbs, Space::with_width(Length::Fill)]
+			.push_maybe(completions)
+			.push_maybe(resolution)
+			.push_maybe(error)
+			.push(aspects)
+			.push_maybe(dirty)
+			.push(
+				container(text(mode.name().to_uppercase()).size(14))
+					.padding([2, 8])
+					.style(style::line_selected(theme)),
+			)
+			.spacing(12)
+			.align_items(Alignment::Center),
+	)
+	.padding([4, 12])
+	.into()
+}
+
+/// What a node is called in the path to the selection.
+fn label(code: &Code, node_id: NodeId) -> String {
+	let Some(placed) = code.get(node_id) else {
+		return "?".into();
+	};
+
+	match &placed.node {
+		Node::Group(children) => {
+			// Groups are named after their first leaf, like the name of a
+			// binding.
+			let name = children
+				.iter()
+				.find(|&&child| code.children(child).is_empty())
+				.map_or_else(|| "group".into(), |&child| label(code, child));
+
+			match placed.scope {
+				Some(scope) => format!("{name} ({})", scope.name()),
+				None => name,
+			}
+		}
+		Node::Text(string) | Node::Definition(string) if string.is_empty() => "empty".into(),
+		Node::Text(string) | Node::Definition(string) | Node::Literal(string) => shorten(string),
+		Node::Reference(target) => code.name(*target).map_or("?".into(), shorten),
+		Node::Operator(operator) | Node::Type(operator) | Node::Import(operator) => {
+			operator.clone()
+		}
+		Node::Implicit(required) => format!("@{required}"),
+		Node::Converter { from, to } => format!("({from} -> {to})"),
+		Node::Member { object, member } => {
+			shorten(&format!("{}.{member}", code.name(*object).unwrap_or("?")))
+		}
+		Node::Alias(_) => match alias::target(code, node_id) {
+			Some(Ok(target)) => format!("alias of {}", label(code, target)),
+			_ => "alias".into(),
+		},
+		Node::Pixel {
+			position: (x, y), ..
+		} => format!("{x},{y}"),
+	}
+}
+
+fn shorten(string: &str) -> String {
+	if string.chars().count() > LABEL_LENGTH {
+		string.chars().take(LABEL_LENGTH - 1).chain(['…']).collect()
+	} else {
+		string.into()
+	}
+}
+
+fn faded(color: Color, opacity: f32) -> Color {
+	Color {
+		a: color.a * opacity,
+		..color
+	}
+}
@@ -0,0 +1,361 @@
+//! Name every color the editor draws with, so the user can pick them.
+use crate::config;
+use iced::{color, Color};
+use std::fmt;
+use std::path::PathBuf;
+
+/// The colors of the editor, one for each thing it draws.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+	pub name: String,
+	pub background: Color,
+	pub text: Color,
+	/// Behind the line under the mouse and the highlighted palette entry.
+	pub line_selection: Color,
+	/// Behind the selected nodes.
+	pub word_selection: Color,
+	/// The gutter branches and the bar next to the code.
+	pub guide: Color,
+	/// The guides while something is selected.
+	pub guide_active: Color,
+	/// The caret shown while typing.
+	pub cursor: Color,
+	/// Error messages and broken code, like references to deleted definitions.
+	pub error: Color,
+	pub semantic: Semantic,
+}
+
+/// The colors of nodes, by what they mean rather than how they're written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Semantic {
+	pub definition: Color,
+	pub reference: Color,
+	pub mutation: Color,
+	/// Values there is only one of, like `Console`.
+	pub unique: Color,
+	pub converter: Color,
+	pub literal: Color,
+}
+
+impl Theme {
+	pub fn dark() -> Theme {
+		Theme {
+			name: "dark".into(),
+			background: color!(0x322442),
+			text: Color::WHITE,
+			line_selection: color!(0x281D34),
+			word_selection: color!(0x6E5091),
+			guide: color!(0x281D34),
+			guide_active: color!(0x6E5091),
+			cursor: color!(0xB89AD9),
+			error: color!(0xFF6B6B),
+			semantic: Semantic {
+				definition: color!(0xF2C57C),
+				reference: color!(0xE4DCEE),
+				mutation: color!(0xF07E8C),
+				unique: color!(0x7CC4F2),
+				converter: color!(0x7DDBC9),
+				literal: color!(0xA9DC8A),
+			},
+		}
+	}
+
+	pub fn light() -> Theme {
+		Theme {
+			name: "light".into(),
+			background: color!(0xF6F2FA),
+			text: color!(0x2A2036),
+			line_selection: color!(0xE6DDF0),
+			word_selection: color!(0xC7B2E0),
+			guide: color!(0xDDD1EA),
+			guide_active: color!(0x8A6AB0),
+			cursor: color!(0x6E5091),
+			error: color!(0xC62828),
+			semantic: Semantic {
+				definition: color!(0x9A6400),
+				reference: color!(0x2A2036),
+				mutation: color!(0xC0304A),
+				unique: color!(0x1F6FB0),
+				converter: color!(0x127A68),
+				literal: color!(0x467A1E),
+			},
+		}
+	}
+
+	/// The theme iced's own widgets, like text inputs and scrollbars, are
+	/// drawn with.
+	pub fn iced(&self) -> iced::Theme {
+		iced::Theme::custom(
+			self.name.clone(),
+			iced::theme::Palette {
+				background: self.background,
+				text: self.text,
+				primary: self.guide_active,
+				success: self.semantic.literal,
+				danger: self.error,
+			},
+		)
+	}
+
+	/// The color a theme file refers to by `name`.
+	fn slot_mut(&mut self, name: &str) -> Option<&mut Color> {
+		Some(match name {
+			"background" => &mut self.background,
+			"text" => &mut self.text,
+			"line_selection" => &mut self.line_selection,
+			"word_selection" => &mut self.word_selection,
+			"guide" => &mut self.guide,
+			"guide_active" => &mut self.guide_active,
+			"cursor" => &mut self.cursor,
+			"error" => &mut self.error,
+			"definition" => &mut self.semantic.definition,
+			"reference" => &mut self.semantic.reference,
+			"mutation" => &mut self.semantic.mutation,
+			"unique" => &mut self.semantic.unique,
+			"converter" => &mut self.semantic.converter,
+			"literal" => &mut self.semantic.literal,
+			_ => return None,
+		})
+	}
+}
+
+/// Something wrong with a theme file, found while loading it.
+#[derive(Debug)]
+pub enum ThemeError {
+	Unreadable(PathBuf, std::io::Error),
+	Malformed {
+		source: String,
+		line: usize,
+	},
+	/// A color is set before any `[name]` header.
+	OutsideTheme {
+		source: String,
+		line: usize,
+	},
+	UnknownSlot {
+		source: String,
+		line: usize,
+		name: String,
+	},
+	UnknownBase {
+		source: String,
+		line: usize,
+		name: String,
+	},
+	InvalidColor {
+		source: String,
+		line: usize,
+		value: String,
+	},
+}
+
+impl fmt::Display for ThemeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ThemeError::Unreadable(path, error) => {
+				write!(f, "cannot read {}: {error}", path.display())
+			}
+			ThemeError::Malformed { source, line } => {
+				write!(f, "{source}:{line}: expected `<slot> = #rrggbb`")
+			}
+			ThemeError::OutsideTheme { source, line } => {
+				write!(f, "{source}:{line}: expected a `[name]` header first")
+			}
+			ThemeError::UnknownSlot { source, line, name } => {
+				write!(f, "{source}:{line}: unknown color `{name}`")
+			}
+			ThemeError::UnknownBase { source, line, name } => {
+				write!(f, "{source}:{line}: unknown theme `{name}`")
+			}
+			ThemeError::InvalidColor {
+				source,
+				line,
+				value,
+			} => write!(f, "{source}:{line}: `{value}` is not a `#rrggbb` color"),
+		}
+	}
+}
+
+/// The themes to choose from, with the one in use.
+#[derive(Debug)]
+pub struct Themes {
+	all: Vec<Theme>,
+	current: usize,
+}
+
+impl Default for Themes {
+	fn default() -> Self {
+		Themes {
+			all: vec![Theme::dark(), Theme::light()],
+			current: 0,
+		}
+	}
+}
+
+impl Themes {
+	/// Loads the presets together with the themes in the user's theme file,
+	/// starting with the first theme the file defines.
+	pub fn load() -> (Themes, Vec<ThemeError>) {
+		let mut themes = Themes::default();
+		let mut errors = Vec::new();
+
+		if let Some(path) = config::path("BISMUTH_THEME", "theme") {
+			match std::fs::read_to_string(&path) {
+				Ok(text) => {
+					let source = path.display().to_string();
+					if let Some(first) = parse(&source, &text, &mut themes.all, &mut errors) {
+						themes.current = first;
+					}
+				}
+				Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+				Err(error) => errors.push(ThemeError::Unreadable(path, error)),
+			}
+		}
+
+		(themes, errors)
+	}
+
+	/// Loads the themes again, staying on the current one if it still
+	/// exists.
+	pub fn reload(&mut self) -> Vec<ThemeError> {
+		let name = self.current().name.clone();
+		let (themes, errors) = Themes::load();
+		*self = themes;
+		self.select(&name);
+		errors
+	}
+
+	pub fn current(&self) -> &Theme {
+		&self.all[self.current]
+	}
+
+	/// Switches to the next theme, going back to the first after the last.
+	pub fn next(&mut self) {
+		self.current = (self.current + 1) % self.all.len();
+	}
+
+	fn select(&mut self, name: &str) {
+		if let Some(index) = self.all.iter().position(|theme| theme.name == name) {
+			self.current = index;
+		}
+	}
+}
+
+/// Parses the themes in `text` into `themes`, replacing the ones with the
+/// same name, and returns the index of the first one.
+///
+/// Each theme starts out as a copy of the dark preset, or of the theme named
+/// by its `base`. Comments take whole lines, since colors start with `#`.
+fn parse(
+	source: &str,
+	text: &str,
+	themes: &mut Vec<Theme>,
+	errors: &mut Vec<ThemeError>,
+) -> Option<usize> {
+	let mut first = None;
+	let mut current = None;
+
+	for (i, line) in text.lines().enumerate() {
+		let line_number = i + 1;
+		let line = line.trim();
+
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+
+		if let Some(name) = line
+			.strip_prefix('[')
+			.and_then(|line| line.strip_suffix(']'))
+		{
+			let theme = Theme {
+				name: name.trim().into(),
+				..Theme::dark()
+			};
+			let index = match themes.iter().position(|t| t.name == theme.name) {
+				Some(index) => {
+					themes[index] = theme;
+					index
+				}
+				None => {
+					themes.push(theme);
+					themes.len() - 1
+				}
+			};
+			first.get_or_insert(index);
+			current = Some(index);
+			continue;
+		}
+
+		let Some((slot, value)) = line.split_once('=') else {
+			errors.push(ThemeError::Malformed {
+				source: source.into(),
+				line: line_number,
+			});
+			continue;
+		};
+		let (slot, value) = (slot.trim(), value.trim());
+
+		let Some(index) = current else {
+			errors.push(ThemeError::OutsideTheme {
+				source: source.into(),
+				line: line_number,
+			});
+			continue;
+		};
+
+		if slot == "base" {
+			match themes.iter().find(|theme| theme.name == value) {
+				Some(base) => {
+					themes[index] = Theme {
+						name: themes[index].name.clone(),
+						..base.clone()
+					}
+				}
+				None => errors.push(ThemeError::UnknownBase {
+					source: source.into(),
+					line: line_number,
+					name: value.into(),
+				}),
+			}
+			continue;
+		}
+
+		let Some(color) = parse_color(value) else {
+			errors.push(ThemeError::InvalidColor {
+				source: source.into(),
+				line: line_number,
+				value: value.into(),
+			});
+			continue;
+		};
+
+		match themes[index].slot_mut(slot) {
+			Some(slot) => *slot = color,
+			None => errors.push(ThemeError::UnknownSlot {
+				source: source.into(),
+				line: line_number,
+				name: slot.into(),
+			}),
+		}
+	}
+
+	first
+}
+
+/// Parses `#rrggbb` or `#rrggbbaa`.
+fn parse_color(text: &str) -> Option<Color> {
+	let hex = text.strip_prefix('#')?;
+	if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+		return None;
+	}
+
+	let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+	let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+
+	Some(Color::from_rgba8(
+		channel(0)?,
+		channel(2)?,
+		channel(4)?,
+		f32::from(alpha) / 255.0,
+	))
+}
@@ -0,0 +1,275 @@
+//! Identify values by their type instead of by name.
+//!
+//! A type written where a value is needed stands for the one value that
+//! provides it: an implicit input of the bindings it's in, or a value
+//! there's only one of outside of any binding, converted through the
+//! converters available there if it's of another type.
+use crate::binding::is_name;
+use crate::code::{Code, Node, NodeId};
+use crate::implicit;
+use crate::scope;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Values there is only ever one of, so they're referred to by their type.
+pub const UNIQUE_VALUES: &[&str] = &["Console", "Window", "Rng"];
+
+/// Types that are always available, besides those of the unique values.
+const BUILT_IN: &[&str] = &["Int", "Float", "Str", "Bool"];
+
+/// Reads a converter signature like `(Str -> Int)`, with or without the colon
+/// before its definition.
+pub fn signature(string: &str) -> Option<(&str, &str)> {
+	let (from, to) = string
+		.trim()
+		.trim_end_matches(':')
+		.strip_prefix('(')?
+		.strip_suffix(')')?
+		.split_once("->")?;
+	let (from, to) = (from.trim(), to.trim());
+
+	(is_name(from) && is_name(to)).then_some((from, to))
+}
+
+/// The node a text node should become once it's typed: a converter if it's
+/// a signature, or a type if it names one available at it.
+pub fn node(code: &Code, node_id: NodeId) -> Option<Node> {
+	let Node::Text(string) = &code.get(node_id)?.node else {
+		return None;
+	};
+
+	if let Some((from, to)) = signature(string) {
+		return Some(Node::Converter {
+			from: from.into(),
+			to: to.into(),
+		});
+	}
+
+	let name = string.trim();
+	let is_type = UNIQUE_VALUES.contains(&name)
+		|| BUILT_IN.contains(&name)
+		|| converters(code, node_id)
+			.iter()
+			.any(|&((from, to), _)| from == name || to == name);
+	is_type.then(|| Node::Type(name.into()))
+}
+
+/// The node a text node should become once it's typed: a literal if it's a
+/// value of a built-in type written out, like `3`, `true` or `"hello"`.
+pub fn literal(code: &Code, node_id: NodeId) -> Option<Node> {
+	let Node::Text(string) = &code.get(node_id)?.node else {
+		return None;
+	};
+
+	let string = string.trim();
+	let is_literal = string.parse::<f64>().is_ok()
+		|| matches!(string, "true" | "false")
+		|| (string.len() >= 2 && string.starts_with('"') && string.ends_with('"'));
+	is_literal.then(|| Node::Literal(string.into()))
+}
+
+/// How a value of a type is provided: an implicit input or a unique value,
+/// converted in turn by each of the converters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provider {
+	/// The type of the value the conversions start from.
+	pub value: String,
+	pub converters: Vec<NodeId>,
+}
+
+impl Provider {
+	/// The types the value goes through, like `Rng → Int → Str`.
+	pub fn describe(&self, code: &Code) -> String {
+		let mut steps = vec![self.value.as_str()];
+		for &converter in &self.converters {
+			if let Some(Node::Converter { to, .. }) = code.get(converter).map(|placed| &placed.node)
+			{
+				steps.push(to);
+			}
+		}
+		steps.join(" → ")
+	}
+}
+
+/// Why a type doesn't stand for a single value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+	/// No value of the type can be made.
+	Missing(String),
+	/// Several values of the type can be made in as few steps.
+	Ambiguous(String, Vec<Provider>),
+}
+
+impl fmt::Display for ResolveError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ResolveError::Missing(required) => write!(f, "nothing provides {required}"),
+			ResolveError::Ambiguous(required, providers) => {
+				write!(f, "{required} can be provided in {} ways", providers.len())
+			}
+		}
+	}
+}
+
+/// Finds the value that provides the required type at a node, through as few
+/// converters as possible.
+pub fn resolve(code: &Code, node_id: NodeId, required: &str) -> Result<Provider, ResolveError> {
+	let converters = converters(code, node_id);
+
+	// Every way of providing a value found so far, with the type it provides.
+	let mut values = implicit::context(code, node_id).unwrap_or(UNIQUE_VALUES.to_vec());
+	values.sort_unstable();
+	values.dedup();
+	let mut providers = values
+		.into_iter()
+		.map(|value| {
+			let provider = Provider {
+				value: value.into(),
+				converters: Vec::new(),
+			};
+			(provider, value)
+		})
+		.collect::<Vec<_>>();
+	let mut reached = HashSet::new();
+
+	while !providers.is_empty() {
+		let found = providers
+			.iter()
+			.filter(|&&(_, provided)| provided == required)
+			.map(|(provider, _)| provider.clone())
+			.collect::<Vec<_>>();
+		match found.len() {
+			0 => {}
+			1 => return Ok(found.into_iter().next().unwrap()),
+			_ => return Err(ResolveError::Ambiguous(required.into(), found)),
+		}
+
+		// Only go on to types that weren't reached in fewer steps.
+		reached.extend(providers.iter().map(|&(_, provided)| provided));
+		providers = providers
+			.iter()
+			.flat_map(|(provider, provided)| {
+				converters
+					.iter()
+					.filter(|&&((from, to), _)| from == *provided && !reached.contains(to))
+					.map(|&((_, to), converter)| {
+						let provider = Provider {
+							value: provider.value.clone(),
+							converters: [&provider.converters[..], &[converter]].concat(),
+						};
+						(provider, to)
+					})
+			})
+			.collect();
+	}
+
+	Err(ResolveError::Missing(required.into()))
+}
+
+/// The converters available at a node with their signature, keeping every
+/// one with the same signature so providing through them is ambiguous.
+fn converters(code: &Code, node_id: NodeId) -> Vec<((&str, &str), NodeId)> {
+	scope::converters(code, node_id)
+		.into_iter()
+		.filter_map(|converter| match &code.get(converter)?.node {
+			Node::Converter { from, to } => Some(((from.as_str(), to.as_str()), converter)),
+			_ => None,
+		})
+		.collect()
+}
+
+/// The type nodes that don't stand for exactly one value.
+pub fn unresolved(code: &Code) -> HashSet<NodeId> {
+	code.walk(code.root())
+		.into_iter()
+		.filter(
+			|&node_id| match code.get(node_id).map(|placed| &placed.node) {
+				Some(Node::Type(required)) => resolve(code, node_id, required).is_err(),
+				_ => false,
+			},
+		)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::projection::parse;
+
+	/// Resolves the last type written in `text` where it's written.
+	fn resolved(text: &str) -> (Code, Result<Provider, ResolveError>) {
+		let code = parse(text).unwrap_or_else(|error| panic!("{error}"));
+		let result = code
+			.walk(code.root())
+			.into_iter()
+			.rev()
+			.find_map(|node_id| match &code.get(node_id)?.node {
+				Node::Type(required) => Some(resolve(&code, node_id, required)),
+				_ => None,
+			})
+			.unwrap_or_else(|| panic!("no type in {text:?}"));
+		(code, result)
+	}
+
+	/// The converters in the code, in reading order.
+	fn converters_in(code: &Code) -> Vec<NodeId> {
+		code.walk(code.root())
+			.into_iter()
+			.filter(|&node_id| {
+				matches!(
+					code.get(node_id).map(|placed| &placed.node),
+					Some(Node::Converter { .. })
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn values() {
+		let (_, result) = resolved("print Console\n");
+		assert_eq!(result.map(|provider| provider.value), Ok("Console".into()));
+		let (_, result) = resolved("print Int\n");
+		assert_eq!(result, Err(ResolveError::Missing("Int".into())));
+
+		// Inside a binding, only its implicit inputs are available.
+		let (_, result) = resolved("f = print Int\n\t@Int\n");
+		assert_eq!(result.map(|provider| provider.value), Ok("Int".into()));
+		let (_, result) = resolved("f = print Rng\n\t@Int\n");
+		assert_eq!(result, Err(ResolveError::Missing("Rng".into())));
+	}
+
+	#[test]
+	fn chaining() {
+		let (code, result) = resolved("(Rng -> Int): a\n(Int -> Str): b\nprint Str\n");
+		let provider = result.unwrap();
+		assert_eq!(provider.converters, converters_in(&code));
+		assert_eq!(provider.describe(&code), "Rng → Int → Str");
+
+		// Through as few converters as possible.
+		let (code, result) =
+			resolved("(Rng -> Int): a\n(Int -> Str): b\n(Rng -> Str): c\nprint Str\n");
+		assert_eq!(result.unwrap().converters, [converters_in(&code)[2]]);
+	}
+
+	#[test]
+	fn ambiguous() {
+		// The same signature twice.
+		let (_, result) = resolved("(Rng -> Int): a\n(Rng -> Int): b\nprint Int\n");
+		assert!(
+			matches!(result, Err(ResolveError::Ambiguous(_, providers)) if providers.len() == 2)
+		);
+
+		// Different values in as many steps.
+		let (_, result) = resolved("(Rng -> Int): a\n(Console -> Int): b\nprint Int\n");
+		let Err(ResolveError::Ambiguous(required, providers)) = result else {
+			panic!("not ambiguous: {result:?}");
+		};
+		assert_eq!(required, "Int");
+		let mut values = providers
+			.into_iter()
+			.map(|provider| provider.value)
+			.collect::<Vec<_>>();
+		values.sort_unstable();
+		assert_eq!(values, ["Console", "Rng"]);
+	}
+}
@@ -1,17 +1,586 @@
-use crate::code::Code;
+use crate::alias::{self, AliasError};
+use crate::animation::{Animations, Purpose};
+use crate::aspect::{AspectFilter, Visibility};
+use crate::code::{Code, Color, Node, NodeId};
+use crate::code_widget::{Column, Separation};
+use crate::document::Document;
+use crate::fill_parent_widget::FillParent;
+use crate::flow_widget::{Break, Flow};
+use crate::gutter_widget::Gutter;
+use crate::highlight::{Highlighter, Highlights};
+use crate::implicit::Calls;
+use crate::keymap::Action;
+use crate::library::Libraries;
+use crate::member;
+use crate::node_widget::Tagged;
+use crate::precedence;
+use crate::projection;
+use crate::style;
+use crate::theme::Theme;
+use iced::widget::{container, row, text, tooltip};
+use iced::Element;
+use std::collections::HashSet;
 
+#[derive(Default)]
 pub struct VisualCode {
-	code: Code,
+	selected: Option<NodeId>,
+	/// The other end of the selected range of siblings, if any.
+	anchor: Option<NodeId>,
+	hovered: Option<NodeId>,
+	/// Whether the selected leaf is being typed into.
+	editing: bool,
+	/// Whether the cursor is in the shown half of its blink.
+	cursor_shown: bool,
+	/// Whether blocks reaching to the end of their group are all kept at
+	/// their own indentation.
+	indent_tails: bool,
+	/// The tail blocks kept at their own indentation while tail dedent is on.
+	indented: HashSet<NodeId>,
+	/// Whether calls show the values filling in their implicit inputs.
+	reveal_implicit: bool,
+	/// The node aliases get pasted of.
+	copied: Option<NodeId>,
 }
 
 impl VisualCode {
-	fn update(&mut self, code: &mut Code, message: Message) {
-		todo!()
+	pub fn update(&mut self, _code: &mut Code, message: Message) {
+		match message {
+			Message::Hover(node) => self.hovered = node,
+			Message::Select(node) => self.selected = node,
+		}
 	}
 
-	fn view(&self, code: &Code) -> iced::Element<Message> {
-		todo!()
+	/// Moves the selection around the tree, skipping hidden nodes.
+	pub fn perform(&mut self, code: &Code, aspects: &AspectFilter, action: Action) {
+		let visible = |node_id: &NodeId| aspects.is_visible(code, *node_id);
+		let first_child = |node_id| code.children(node_id).iter().copied().find(visible);
+
+		let Some(selected) = self.selected else {
+			self.selected = match action {
+				Action::SelectRoot => Some(code.root()),
+				Action::ClearSelection => None,
+				_ => first_child(code.root()),
+			};
+			return;
+		};
+
+		let parent = code.get(selected).and_then(|placed| placed.parent());
+		let sibling = |offset: isize| {
+			let siblings = code.children(parent?);
+			let mut index = siblings.iter().position(|&node| node == selected)?;
+			loop {
+				index = index.checked_add_signed(offset)?;
+				let sibling = *siblings.get(index)?;
+				if visible(&sibling) {
+					return Some(sibling);
+				}
+			}
+		};
+
+		self.selected = match action {
+			Action::SelectPrevious => sibling(-1).or(Some(selected)),
+			Action::SelectNext => sibling(1).or(Some(selected)),
+			Action::SelectParent => parent.or(Some(selected)),
+			Action::SelectFirstChild => first_child(selected).or(Some(selected)),
+			Action::SelectRoot => Some(code.root()),
+			Action::ClearSelection => None,
+			_ => Some(selected),
+		};
+	}
+
+	pub fn selected(&self) -> Option<NodeId> {
+		self.selected
+	}
+
+	/// Forgets the nodes that are no longer in the code, like after undoing
+	/// their insertion.
+	pub fn forget_removed(&mut self, code: &Code) {
+		for node in [
+			&mut self.selected,
+			&mut self.anchor,
+			&mut self.hovered,
+			&mut self.copied,
+		] {
+			*node = node.filter(|&node_id| code.get(node_id).is_some());
+		}
+		self.indented.retain(|&node_id| code.get(node_id).is_some());
+	}
+
+	/// Moves the selection out of hidden nodes, to their closest visible
+	/// ancestor.
+	pub fn reveal_selection(&mut self, code: &Code, aspects: &AspectFilter) {
+		while let Some(selected) = self.selected {
+			if aspects.is_visible(code, selected) {
+				break;
+			}
+			self.selected = code.get(selected).and_then(|placed| placed.parent());
+		}
+		self.anchor = self
+			.anchor
+			.filter(|&anchor| aspects.is_visible(code, anchor));
+		self.hovered = None;
+	}
+
+	/// Starts extending the selection into a range from the selected node.
+	pub fn start_range(&mut self) {
+		self.anchor = self.selected;
+	}
+
+	pub fn end_range(&mut self) {
+		self.anchor = None;
+	}
+
+	pub fn set_editing(&mut self, editing: bool) {
+		self.editing = editing;
+		self.cursor_shown = true;
+	}
+
+	/// Shows the cursor if it was hidden and hides it otherwise, returning
+	/// whether it's shown now.
+	pub fn blink(&mut self) -> bool {
+		self.cursor_shown = !self.cursor_shown;
+		self.cursor_shown
+	}
+
+	pub fn toggle_tail_dedent(&mut self) {
+		self.indent_tails = !self.indent_tails;
+	}
+
+	pub fn toggle_implicit_arguments(&mut self) {
+		self.reveal_implicit = !self.reveal_implicit;
+	}
+
+	/// Remembers the selected node to paste aliases of. Returns whether
+	/// anything was selected.
+	pub fn copy(&mut self) -> bool {
+		self.copied = self.selected;
+		self.copied.is_some()
+	}
+
+	pub fn copied(&self) -> Option<NodeId> {
+		self.copied
+	}
+
+	/// Keeps the tail block of the selected group, or the selected tail block
+	/// itself, at its own indentation, or dedents it again. Returns whether
+	/// there was a tail block to toggle.
+	pub fn toggle_dedent(&mut self, code: &Code, aspects: &AspectFilter) -> bool {
+		let Some(selected) = self.selected else {
+			return false;
+		};
+		let parent = code.get(selected).and_then(|placed| placed.parent());
+
+		let tail = tail_block(code, aspects, selected).or_else(|| {
+			parent
+				.and_then(|parent| tail_block(code, aspects, parent))
+				.filter(|&tail| tail == selected)
+		});
+		let Some(tail) = tail else {
+			return false;
+		};
+
+		if !self.indented.remove(&tail) {
+			self.indented.insert(tail);
+		}
+		true
+	}
+
+	/// Whether the node lies between the anchor and the selected node.
+	fn in_range(&self, code: &Code, node_id: NodeId) -> bool {
+		let (Some(anchor), Some(selected)) = (self.anchor, self.selected) else {
+			return false;
+		};
+		let Some(parent) = code.get(anchor).and_then(|placed| placed.parent()) else {
+			return false;
+		};
+
+		let siblings = code.children(parent);
+		let position = |node| siblings.iter().position(|&sibling| sibling == node);
+
+		match (position(anchor), position(selected), position(node_id)) {
+			(Some(anchor), Some(selected), Some(node)) => {
+				anchor.min(selected) <= node && node <= anchor.max(selected)
+			}
+			_ => false,
+		}
+	}
+
+	/// Shows the code of the document this is the view state of.
+	pub fn view<'a>(
+		&'a self,
+		document: &'a Document,
+		animations: &Animations,
+		theme: &Theme,
+		highlighter: &Highlighter,
+		aspects: &AspectFilter,
+		libraries: &Libraries,
+	) -> Element<'a, Message> {
+		let code = &document.code;
+		let analysis = document.analysis();
+		let scene = Scene {
+			animations,
+			theme,
+			highlights: highlighter.highlight(code),
+			aspects,
+			out_of_scope: &analysis.out_of_scope,
+			ambiguous: precedence::ambiguous(code),
+			unresolved: analysis
+				.unresolved_types
+				.iter()
+				.copied()
+				.chain(document.unresolved_imports(libraries).iter().copied())
+				.chain(analysis.unresolved_members.iter().copied())
+				.chain(analysis.unfilled.iter().copied())
+				.collect(),
+			calls: &analysis.calls,
+		};
+		self.view_node(code, code.root(), &scene, Visibility::Shown, 1.0)
+	}
+
+	/// Shows a node, no more visible than its parent's `visibility`, and
+	/// faded in as far as the node showing it, so everything inside a freshly
+	/// inserted group or alias fades in with it.
+	fn view_node<'a>(
+		&'a self,
+		code: &'a Code,
+		node_id: NodeId,
+		scene: &Scene,
+		visibility: Visibility,
+		fade: f32,
+	) -> Element<'a, Message> {
+		let Scene {
+			animations,
+			theme,
+			highlights,
+			aspects,
+			out_of_scope,
+			ambiguous,
+			unresolved,
+			calls,
+		} = scene;
+
+		let Some(placed) = code.get(node_id) else {
+			return text("?").into();
+		};
+
+		let visibility = visibility.max(aspects.own_visibility(code, node_id));
+		let fade = fade * animations.value_or(Purpose::Insertion(node_id), 1.0);
+		let faded = iced::Color {
+			a: fade * visibility.opacity(),
+			..highlights
+				.get(&node_id)
+				.map_or(theme.text, |category| category.color(theme))
+		};
+
+		let content: Element<Message> = match &placed.node {
+			Node::Group(group) => {
+				let dedented = |group| {
+					tail_block(code, aspects, group)
+						.filter(|tail| !self.indent_tails && !self.indented.contains(tail))
+				};
+				let tail = dedented(node_id);
+
+				let shown =
+					|child: &&NodeId| aspects.own_visibility(code, **child) != Visibility::Hidden;
+				let view = |child: NodeId| self.view_node(code, child, scene, visibility, fade);
+
+				let head = projection::head(code, node_id);
+				if node_id == code.root() || head == 0 {
+					// Lines without words of their own to go under are just
+					// siblings, with more space around the ones that have
+					// blocks, which make paragraphs of their own.
+					let lines = group.iter().filter(shown).copied().collect::<Vec<_>>();
+					let has_blocks = |line: NodeId| {
+						is_group(code, line)
+							&& projection::head(code, line) < code.children(line).len()
+					};
+					lines
+						.iter()
+						.enumerate()
+						.fold(Column::new().group_spacing(12), |column, (i, &line)| {
+							let separation = match i.checked_sub(1).map(|i| lines[i]) {
+								Some(previous) if has_blocks(previous) || has_blocks(line) => {
+									Separation::Group
+								}
+								_ => Separation::Line,
+							};
+							column.push_separated(view(line), separation)
+						})
+						.into()
+				} else {
+					// The words of the line wrap to the width of the window,
+					// preferably before an operator, and its blocks go under
+					// it.
+					let (words, blocks) = group.split_at(head);
+					let line = Flow::new().indent(24);
+					let line = words.iter().filter(shown).fold(line, |line, child| {
+						let is_operator = matches!(
+							code.get(*child).map(|placed| &placed.node),
+							Some(Node::Operator(_))
+						);
+						let break_before = if is_operator {
+							Break::Preferred
+						} else {
+							Break::Allowed
+						};
+						line.push_with_break(view(*child), break_before)
+					});
+
+					let blocks = blocks
+						.iter()
+						.filter(shown)
+						.filter(|&&child| Some(child) != tail)
+						.collect::<Vec<_>>();
+					if blocks.is_empty() && tail.is_none() {
+						line.into()
+					} else {
+						let blocks = (!blocks.is_empty()).then(|| {
+							// The guide beside the blocks lights up while the
+							// selection is inside one of them.
+							let path = self
+								.selected
+								.map_or(Vec::new(), |selected| code.path(selected));
+							let guide = FillParent::new()
+								.width(2.0)
+								.radius(1.0)
+								.color(theme.guide)
+								.active_color(theme.guide_active)
+								.active(blocks.iter().any(|block| path.contains(block)))
+								.intensity(animations.value_or(Purpose::Guide, 1.0));
+							let blocks =
+								Gutter::with_children(blocks.into_iter().map(|&child| view(child)))
+									.color(theme.guide);
+							row![guide, blocks].spacing(4)
+						});
+
+						// A dedented block is marked by a bar along it, whether
+						// or not it has blocks of its own.
+						let tail = tail.map(|tail| {
+							let marker = FillParent::new()
+								.width(2.0)
+								.radius(1.0)
+								.color(theme.guide_active);
+							row![marker, view(tail)].spacing(4)
+						});
+
+						Column::new()
+							.push(line)
+							.push_maybe(blocks)
+							.push_maybe(tail)
+							.into()
+					}
+				}
+			}
+			Node::Text(string) | Node::Definition(string)
+				if self.editing && self.selected == Some(node_id) =>
+			{
+				row![
+					text(string).style(faded),
+					text("|").style(iced::Color {
+						a: animations.value_or(
+							Purpose::CursorBlink,
+							if self.cursor_shown { 1.0 } else { 0.0 },
+						),
+						..theme.cursor
+					}),
+				]
+				.into()
+			}
+			Node::Text(string) | Node::Definition(string) | Node::Literal(string) => {
+				text(string).style(faded).into()
+			}
+			Node::Operator(operator) if ambiguous.contains(&node_id) => text(operator)
+				.style(iced::Color {
+					a: faded.a,
+					..theme.error
+				})
+				.into(),
+			Node::Operator(operator) => text(operator).style(faded).into(),
+			Node::Type(name) | Node::Import(name) if unresolved.contains(&node_id) => text(name)
+				.style(iced::Color {
+					a: faded.a,
+					..theme.error
+				})
+				.into(),
+			Node::Type(name) | Node::Import(name) => text(name).style(faded).into(),
+			Node::Converter { from, to } => text(format!("({from} -> {to})")).style(faded).into(),
+			Node::Reference(target) => {
+				let reference = match code.name(*target) {
+					Some(name)
+						if out_of_scope.contains(&node_id) || unresolved.contains(&node_id) =>
+					{
+						text(name).style(iced::Color {
+							a: faded.a,
+							..theme.error
+						})
+					}
+					Some(name) => text(name).style(faded),
+					// The definition was deleted.
+					None => text("<deleted>").style(iced::Color {
+						a: faded.a,
+						..theme.error
+					}),
+				};
+
+				if !self.reveal_implicit {
+					reference.into()
+				} else {
+					let arguments =
+						calls
+							.get(&node_id)
+							.into_iter()
+							.flatten()
+							.map(|(required, provider)| {
+								let (argument, color) = match provider {
+									Ok(provider) => (provider.describe(code), theme.text),
+									Err(_) => (format!("no {required}"), theme.error),
+								};
+								text(format!("⟨{argument}⟩"))
+									.style(iced::Color {
+										a: faded.a * 0.6,
+										..color
+									})
+									.into()
+							});
+					row(std::iter::once(reference.into()).chain(arguments))
+						.spacing(6)
+						.into()
+				}
+			}
+			Node::Implicit(required) => text(format!("@{required}")).style(faded).into(),
+			// The node itself is shown, so selecting or editing anything in
+			// it selects or edits the node wherever it's shown.
+			Node::Alias(_) => match alias::target(code, node_id) {
+				Some(Ok(target)) => {
+					{ container(self.view_node(code, target, scene, visibility, fade)) }
+						.padding(2)
+						.style(style::aliased(theme))
+						.into()
+				}
+				error => {
+					let shown = match error {
+						Some(Err(AliasError::Recursive)) => "<alias>",
+						_ => "<deleted>",
+					};
+					text(shown)
+						.style(iced::Color {
+							a: faded.a,
+							..theme.error
+						})
+						.into()
+				}
+			},
+			Node::Member { object, member } => {
+				let name = format!("{}.{member}", code.name(*object).unwrap_or("<deleted>"));
+				let color = if unresolved.contains(&node_id) {
+					theme.error
+				} else {
+					faded
+				};
+
+				// Stored and computed members look the same, so which one it
+				// is shows on hover.
+				let inspector = match member::resolve(code, node_id) {
+					Some(Ok(member)) => member.kind.name().to_owned(),
+					Some(Err(error)) => error.to_string(),
+					None => String::new(),
+				};
+				tooltip(
+					text(name).style(iced::Color {
+						a: faded.a,
+						..color
+					}),
+					container(text(inspector).size(14))
+						.padding([2, 8])
+						.style(style::palette(theme)),
+					tooltip::Position::Bottom,
+				)
+				.into()
+			}
+			Node::Pixel {
+				position: (x, y),
+				color,
+			} => text(format!(
+				"{x},{y} = {}",
+				match color {
+					Color::Red => "red",
+					Color::Green => "green",
+					Color::Blue => "blue",
+				}
+			))
+			.style(faded)
+			.into(),
+		};
+
+		let content = container(content).padding([0, 6]);
+		let content = if self.selected == Some(node_id) || self.in_range(code, node_id) {
+			content.style(style::word_selected(
+				theme,
+				animations.value_or(Purpose::Selection, 1.0),
+			))
+		} else if self.hovered == Some(node_id) {
+			content.style(style::line_selected(theme))
+		} else {
+			content
+		};
+
+		Tagged::new(node_id, content).into()
 	}
 }
 
-pub enum Message {}
+/// Everything nodes are shown with besides the code itself.
+struct Scene<'a> {
+	animations: &'a Animations,
+	theme: &'a Theme,
+	highlights: Highlights,
+	aspects: &'a AspectFilter,
+	/// The references to definitions not available where they're used.
+	out_of_scope: &'a HashSet<NodeId>,
+	/// The operators mixed with others they have no precedence relationship
+	/// with.
+	ambiguous: HashSet<NodeId>,
+	/// The types that don't stand for exactly one value, the imports and
+	/// member accesses that don't refer to exactly one item, and the calls
+	/// missing an implicit input.
+	unresolved: HashSet<NodeId>,
+	/// The values filling in the implicit inputs of every call that has
+	/// any.
+	calls: &'a Calls,
+}
+
+fn is_group(code: &Code, node_id: NodeId) -> bool {
+	matches!(
+		code.get(node_id).map(|placed| &placed.node),
+		Some(Node::Group(_))
+	)
+}
+
+/// The last child of a group if it's a block that reaches to the end of the
+/// group, which can be shown at the group's indentation instead of its own,
+/// like the rest of a do-block. A group of nothing but lines has none, as it
+/// has no line of its own for the block to come after.
+fn tail_block(code: &Code, aspects: &AspectFilter, group: NodeId) -> Option<NodeId> {
+	let head = projection::head(code, group);
+	if group == code.root() || head == 0 {
+		return None;
+	}
+
+	let shown = |node_id: &&NodeId| aspects.own_visibility(code, **node_id) != Visibility::Hidden;
+	let children = code
+		.children(group)
+		.iter()
+		.filter(shown)
+		.collect::<Vec<_>>();
+	let (&&last, rest) = children.split_last()?;
+	let is_on_line = code.children(group)[..head].contains(&last);
+
+	let is_block = is_group(code, last) && code.children(last).iter().any(|child| shown(&child));
+
+	(is_block && !is_on_line && !rest.is_empty()).then_some(last)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+	Hover(Option<NodeId>),
+	Select(Option<NodeId>),
+}